@@ -0,0 +1,33 @@
+//! Compare the sequential and parallel scoring paths with criterion baselines:
+//!
+//! cargo bench --bench scoring_benchmark -- --save-baseline sequential
+//! cargo bench --bench scoring_benchmark --features rayon -- --baseline sequential
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use you_autocomplete_me::search::Search;
+use you_autocomplete_me::{TrieNode, YouAutoCompleteMe};
+
+fn score_candidates(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Score");
+    for i in [100, 1000, 10000].iter() {
+        let mut trie = TrieNode::default();
+        for _ in 0..*i {
+            let b = uuid::Uuid::new_v4().to_string();
+            trie.insert(b.as_str());
+        }
+        let completer = YouAutoCompleteMe::new(&trie);
+
+        group.bench_function(BenchmarkId::new("Search 2 Characters", i), |b| {
+            b.iter(|| completer.search(&Search::new("ab")))
+        });
+
+        group.bench_function(BenchmarkId::new("Fuzzy Search 4 Characters", i), |b| {
+            let search = Search::builder().search("a1b2").fuzzy(true).build();
+            b.iter(|| completer.search(&search))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, score_candidates);
+criterion_main!(benches);
@@ -0,0 +1,192 @@
+//! # Alias Module
+//!
+//! Aliases expand a search before the phrase book is consulted, so that "tv" also completes
+//! phrases containing "television". The whole search is expanded, and so is each of its
+//! tokens on its own, matching either the token or any of the terms it expands to. Matches
+//! found through an alias report it in [Match::alias](crate::Match::alias).
+//!
+//! ## Example
+//!
+//! ```
+//! use you_autocomplete_me::alias::Aliases;
+//! use you_autocomplete_me::{Completer, TrieNode, YouAutoCompleteMe};
+//! let mut phrase_book = TrieNode::default();
+//! phrase_book.insert("Television settings");
+//! phrase_book.insert("tv guide");
+//! let aliases = Aliases::new()
+//!     .with("tv", "television")
+//!     .with("prefs", "settings");
+//! let completer = YouAutoCompleteMe::builder(&phrase_book)
+//!     .with_aliases(&aliases)
+//!     .build();
+//!
+//! let matches = completer.complete("tv");
+//! assert_eq!(matches[0].phrase, "Television settings");
+//! assert_eq!(matches[0].alias, Some("tv"));
+//! assert_eq!(matches[1].phrase, "tv guide");
+//! assert_eq!(matches[1].alias, None);
+//!
+//! let matches = completer.complete("tv prefs");
+//! assert_eq!(matches[0].phrase, "Television settings");
+//! ```
+
+use std::collections::HashMap;
+
+use crate::query::{Query, Term, TermKind};
+
+/// Aliases along with the canonical terms they expand to
+#[derive(Debug, Clone, Default)]
+pub struct Aliases {
+    /// Canonical terms by the lowercased alias, along with the alias as it was registered
+    aliases: HashMap<String, (String, Vec<String>)>,
+}
+
+impl Aliases {
+    /// Creates an empty set of aliases
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Registers an alias for a canonical term. An alias may expand to several terms,
+    /// and aliases are matched case insensitively.
+    pub fn with(mut self, alias: &str, canonical: &str) -> Self {
+        let alias = alias.trim();
+        if alias.is_empty() || canonical.trim().is_empty() {
+            return self;
+        }
+        let (_, canonicals) = self
+            .aliases
+            .entry(alias.to_lowercase())
+            .or_insert_with(|| (alias.to_string(), Vec::new()));
+        if !canonicals.iter().any(|c| c == canonical) {
+            canonicals.push(canonical.to_string());
+        }
+        self
+    }
+
+    /// The alias as it was registered and the terms it expands to
+    pub(crate) fn get(&self, alias: &str) -> Option<(&str, &[String])> {
+        self.aliases
+            .get(&alias.to_lowercase())
+            .map(|(alias, canonicals)| (alias.as_str(), canonicals.as_slice()))
+    }
+
+    /// The search followed by every expansion of the whole search, along with the alias each
+    /// expansion used
+    pub(crate) fn expand<'s>(&'s self, search: &str) -> Vec<(String, Option<&'s str>)> {
+        let mut expansions: Vec<(String, Option<&'s str>)> = vec![(search.to_string(), None)];
+        if let Some((alias, canonicals)) = self.get(search.trim()) {
+            expansions.extend(canonicals.iter().map(|c| (c.clone(), Some(alias))));
+        }
+        expansions
+    }
+
+    /// The query with the terms every plain term expands to as alternatives of it, so that
+    /// each term is expanded on its own rather than every combination of them.
+    /// `None` when no term is an alias.
+    pub(crate) fn expand_query<'q>(&'q self, query: Query<'q>) -> Option<Query<'q>> {
+        let expands = |term: &Term<'_>| {
+            term.kind == TermKind::Plain && !term.negated && self.get(term.text).is_some()
+        };
+        if !query.groups().iter().flatten().any(expands) {
+            return None;
+        }
+        Some(query.with_alternatives(|term| {
+            match self.get(term.text) {
+                Some((alias, canonicals)) if expands(term) => canonicals
+                    .iter()
+                    .map(|canonical| Term {
+                        text: canonical,
+                        kind: TermKind::Plain,
+                        negated: false,
+                        alias: Some(alias),
+                    })
+                    .collect(),
+                _ => Vec::new(),
+            }
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search::Search;
+
+    fn expansions(aliases: &Aliases, search: &str) -> Vec<(String, Option<String>)> {
+        aliases
+            .expand(search)
+            .into_iter()
+            .map(|(search, alias)| (search, alias.map(String::from)))
+            .collect()
+    }
+
+    #[test]
+    fn expand_whole_search_and_tokens() {
+        let aliases = Aliases::new()
+            .with("TV", "television")
+            .with("tv", "telly")
+            .with("big apple", "new york");
+
+        assert_eq!(
+            expansions(&aliases, "tv"),
+            vec![
+                ("tv".into(), None),
+                ("television".into(), Some("TV".into())),
+                ("telly".into(), Some("TV".into())),
+            ]
+        );
+        assert_eq!(
+            expansions(&aliases, "Big Apple"),
+            vec![
+                ("Big Apple".into(), None),
+                ("new york".into(), Some("big apple".into())),
+            ]
+        );
+        assert_eq!(
+            expansions(&aliases, "tv stand"),
+            vec![("tv stand".into(), None)]
+        );
+    }
+
+    #[test]
+    fn expand_query_adds_alternatives_to_each_term() {
+        let aliases = Aliases::new()
+            .with("tv", "television")
+            .with("tv", "telly")
+            .with("prefs", "settings");
+        let search = Search::new("tv stand tv prefs");
+        let query = aliases.expand_query(Query::tokens(&search)).unwrap();
+
+        let groups: Vec<Vec<(&str, Option<&str>)>> = query
+            .groups()
+            .iter()
+            .map(|group| group.iter().map(|term| (term.text, term.alias)).collect())
+            .collect();
+        let tv = vec![
+            ("tv", None),
+            ("television", Some("tv")),
+            ("telly", Some("tv")),
+        ];
+        assert_eq!(
+            groups,
+            vec![
+                tv.clone(),
+                vec![("stand", None)],
+                tv,
+                vec![("prefs", None), ("settings", Some("prefs"))],
+            ]
+        );
+        assert!(aliases
+            .expand_query(Query::tokens(&Search::new("radio stand")))
+            .is_none());
+    }
+
+    #[test]
+    fn expand_without_aliases_is_the_search() {
+        assert_eq!(
+            expansions(&Aliases::new().with("", "nothing"), "tv"),
+            vec![("tv".into(), None)]
+        );
+    }
+}
@@ -0,0 +1,398 @@
+//! # Analyzer Module
+//!
+//! Analyzers preprocess phrases when they are inserted into the phrase book and searches before
+//! they are matched. The phrase book applies the same [Analyzer] to both, so a search only has
+//! to match the analyzed form of a phrase, while matches still refer to the original text.
+//!
+//! ## Stock Analyzers
+//!
+//! - [Normalizer](crate::normalize::Normalizer): Unicode normalization, the default
+//! - [Lowercase]: lowercases everything, so searches never match case sensitively
+//! - [AccentFolding]: removes accents, "é" becomes "e"
+//! - [PunctuationStripping]: removes punctuation, "don't" becomes "dont"
+//! - [WidthFolding]: folds fullwidth and halfwidth forms, "ＡＢＣ" becomes "ABC"
+//! - [Chain]: applies several analyzers in order
+//!
+//! ## Example
+//!
+//! ```
+//! use you_autocomplete_me::analyzer::{AccentFolding, Chain, PunctuationStripping};
+//! use you_autocomplete_me::TrieNode;
+//! let mut phrase_book = TrieNode::default().with_analyzer(
+//!     Chain::new()
+//!         .with(AccentFolding)
+//!         .with(PunctuationStripping),
+//! );
+//! phrase_book.insert("Don't stop me now!");
+//! phrase_book.insert("Café");
+//!
+//! assert!(phrase_book.search("dont").contains("Don't stop me now!"));
+//! assert!(phrase_book.search("cafe").contains("Café"));
+//! ```
+
+use std::collections::HashSet;
+use std::fmt;
+
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::UnicodeNormalization;
+
+/// Preprocesses text before it is indexed or matched
+pub trait Analyzer: fmt::Debug + Send + Sync {
+    /// Analyzes the text, replacing, removing or adding characters. Characters added with
+    /// [AnalyzedText::map] or [AnalyzedText::push] keep the origin they came from, while
+    /// origins past the end of the original text are moved onto its last character.
+    fn analyze(&self, text: AnalyzedText) -> AnalyzedText;
+}
+
+/// Text being analyzed, along with the position of the original character each
+/// analyzed character came from
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AnalyzedText {
+    pub(crate) chars: Vec<char>,
+    pub(crate) origins: Vec<usize>,
+}
+
+impl AnalyzedText {
+    /// Creates the text before any analysis
+    pub fn new(text: &str) -> Self {
+        let chars: Vec<char> = text.chars().collect();
+        let origins = (0..chars.len()).collect();
+        Self { chars, origins }
+    }
+
+    /// The analyzed characters
+    pub fn chars(&self) -> &[char] {
+        &self.chars
+    }
+
+    /// The original character position of every analyzed character
+    pub fn origins(&self) -> &[usize] {
+        &self.origins
+    }
+
+    /// Replaces every character with the characters pushed by `f`,
+    /// which keep the origin of the character they replace
+    /// ```
+    /// use you_autocomplete_me::analyzer::AnalyzedText;
+    /// let text = AnalyzedText::new("a-b").map(|c, out| {
+    ///     if c != '-' {
+    ///         out.push(c);
+    ///         out.push(c);
+    ///     }
+    /// });
+    /// assert_eq!(text.chars(), &['a', 'a', 'b', 'b']);
+    /// assert_eq!(text.origins(), &[0, 0, 2, 2]);
+    /// ```
+    pub fn map(self, mut f: impl FnMut(char, &mut Vec<char>)) -> Self {
+        let mut mapped = Self::default();
+        let mut buffer = Vec::new();
+        for (c, origin) in self.chars.into_iter().zip(self.origins) {
+            f(c, &mut buffer);
+            for c in buffer.drain(..) {
+                mapped.push(c, origin);
+            }
+        }
+        mapped
+    }
+
+    /// Appends a character which came from the original character at position `origin`,
+    /// for analyzers building their text one character at a time
+    /// ```
+    /// use you_autocomplete_me::analyzer::AnalyzedText;
+    /// let mut text = AnalyzedText::default();
+    /// for (c, origin) in [('a', 0), ('n', 0), ('d', 0)] {
+    ///     text.push(c, origin);
+    /// }
+    /// assert_eq!(text.chars(), &['a', 'n', 'd']);
+    /// ```
+    pub fn push(&mut self, c: char, origin: usize) {
+        self.chars.push(c);
+        self.origins.push(origin);
+    }
+
+    /// Keeps the origins within the original text of `len` characters, as analyzers may
+    /// create new text without them
+    fn within(mut self, len: usize) -> Self {
+        if len == 0 {
+            return Self::default();
+        }
+        for origin in &mut self.origins {
+            *origin = (*origin).min(len - 1);
+        }
+        self
+    }
+
+    /// The analyzed text as a string
+    pub(crate) fn key(&self) -> String {
+        self.chars.iter().collect()
+    }
+}
+
+/// Analyzes the text with the phrase book's analyzer, case folding it when matching
+/// case insensitively
+pub(crate) fn analyze(analyzer: &dyn Analyzer, text: &str, fold: bool) -> AnalyzedText {
+    let len = text.chars().count();
+    let analyzed = analyzer.analyze(AnalyzedText::new(text)).within(len);
+    match fold {
+        true => analyzed.map(fold_case),
+        false => analyzed,
+    }
+}
+
+/// The analyzer of a phrase book, along with the transliterators adding alternative keys
+/// for its phrases
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Analysis<'a> {
+    analyzer: &'a dyn Analyzer,
+    transliterators: &'a [Box<dyn Analyzer>],
+    stop_words: Option<&'a HashSet<String>>,
+}
+
+impl<'a> Analysis<'a> {
+    pub(crate) fn new(analyzer: &'a dyn Analyzer) -> Self {
+        Self {
+            analyzer,
+            transliterators: &[],
+            stop_words: None,
+        }
+    }
+
+    pub(crate) fn with_transliterators(mut self, transliterators: &'a [Box<dyn Analyzer>]) -> Self {
+        self.transliterators = transliterators;
+        self
+    }
+
+    /// Stop words within which the phrase book indexes no suffixes
+    pub(crate) fn with_stop_words(mut self, stop_words: &'a HashSet<String>) -> Self {
+        self.stop_words = Some(stop_words);
+        self
+    }
+
+    pub(crate) fn stop_words(&self) -> Option<&'a HashSet<String>> {
+        self.stop_words
+    }
+
+    /// Analyzes a search
+    pub(crate) fn search(&self, text: &str, fold: bool) -> AnalyzedText {
+        analyze(self.analyzer, text, fold)
+    }
+
+    /// Analyzes a phrase, followed by every transliteration which differs from it
+    pub(crate) fn phrase(&self, text: &str, fold: bool) -> Vec<AnalyzedText> {
+        let len = text.chars().count();
+        let analyzed = self.analyzer.analyze(AnalyzedText::new(text)).within(len);
+        let mut texts = vec![analyzed.clone()];
+        for transliterator in self.transliterators {
+            let transliterated = transliterator.analyze(analyzed.clone()).within(len);
+            if !texts.contains(&transliterated) {
+                texts.push(transliterated);
+            }
+        }
+        match fold {
+            true => texts.into_iter().map(|text| text.map(fold_case)).collect(),
+            false => texts,
+        }
+    }
+}
+
+/// Full case folding, as the full (F) and common (C) mappings of CaseFolding.txt. Those are
+/// the lowercase of the uppercase of the lowercase, except for the dotless i, which does not
+/// fold, and Cherokee, which folds to uppercase.
+pub(crate) fn fold_case(c: char, out: &mut Vec<char>) {
+    match c {
+        'ı' => out.push(c),
+        _ => out.extend(
+            c.to_lowercase()
+                .flat_map(char::to_uppercase)
+                .flat_map(char::to_lowercase)
+                .map(fold_cherokee),
+        ),
+    }
+}
+
+fn fold_cherokee(c: char) -> char {
+    let upper = match c {
+        '\u{AB70}'..='\u{ABBF}' => u32::from(c) - 0xAB70 + 0x13A0,
+        '\u{13F8}'..='\u{13FD}' => u32::from(c) - 8,
+        _ => return c,
+    };
+    char::from_u32(upper).unwrap_or(c)
+}
+
+/// Case folds everything, so searches never match case sensitively
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Lowercase;
+
+impl Analyzer for Lowercase {
+    fn analyze(&self, text: AnalyzedText) -> AnalyzedText {
+        text.map(fold_case)
+    }
+}
+
+/// Removes accents and other combining marks
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AccentFolding;
+
+impl Analyzer for AccentFolding {
+    fn analyze(&self, text: AnalyzedText) -> AnalyzedText {
+        text.map(|c, out| out.extend(c.to_string().nfd().filter(|c| !is_combining_mark(*c))))
+    }
+}
+
+/// Removes ASCII and common Unicode punctuation
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PunctuationStripping;
+
+impl Analyzer for PunctuationStripping {
+    fn analyze(&self, text: AnalyzedText) -> AnalyzedText {
+        text.map(|c, out| {
+            if !is_punctuation(c) {
+                out.push(c)
+            }
+        })
+    }
+}
+
+fn is_punctuation(c: char) -> bool {
+    c.is_ascii_punctuation()
+        || matches!(
+            c,
+            '¡' | '§'
+                | '«'
+                | '¶'
+                | '·'
+                | '»'
+                | '¿'
+                | '\u{2010}'..='\u{2027}'
+                | '\u{2030}'..='\u{205E}'
+                | '\u{3001}'..='\u{3003}'
+                | '\u{3008}'..='\u{3011}'
+                | '\u{3014}'..='\u{301F}'
+                | '\u{FF01}'..='\u{FF0F}'
+                | '\u{FF1A}'..='\u{FF20}'
+                | '\u{FF3B}'..='\u{FF40}'
+                | '\u{FF5B}'..='\u{FF65}'
+        )
+}
+
+/// Folds fullwidth ASCII to ASCII, the ideographic space to a space and halfwidth katakana to
+/// katakana. Halfwidth voiced sound marks become combining marks, chain a
+/// [Normalizer](crate::normalize::Normalizer) afterwards to compose them.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WidthFolding;
+
+impl Analyzer for WidthFolding {
+    fn analyze(&self, text: AnalyzedText) -> AnalyzedText {
+        text.map(|c, out| match c {
+            '\u{FF01}'..='\u{FF5E}' => {
+                out.extend(char::from_u32(u32::from(c) - 0xFF01 + 0x21));
+            }
+            '\u{3000}' => out.push(' '),
+            '\u{FF61}'..='\u{FF9F}' => out.extend(c.to_string().nfkc()),
+            _ => out.push(c),
+        })
+    }
+}
+
+/// Applies several analyzers, in the order they were added
+#[derive(Debug, Default)]
+pub struct Chain {
+    analyzers: Vec<Box<dyn Analyzer>>,
+}
+
+impl Chain {
+    /// Creates a chain without any analyzers
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Adds an analyzer to the end of the chain
+    pub fn with(mut self, analyzer: impl Analyzer + 'static) -> Self {
+        self.analyzers.push(Box::new(analyzer));
+        self
+    }
+}
+
+impl Analyzer for Chain {
+    fn analyze(&self, text: AnalyzedText) -> AnalyzedText {
+        self.analyzers
+            .iter()
+            .fold(text, |text, analyzer| analyzer.analyze(text))
+    }
+}
+
+/// Expands `&` into " and " by creating new text, losing the origins like an analyzer
+/// outside the crate might
+#[cfg(test)]
+#[derive(Debug)]
+pub(crate) struct AndExpansion;
+
+#[cfg(test)]
+impl Analyzer for AndExpansion {
+    fn analyze(&self, text: AnalyzedText) -> AnalyzedText {
+        AnalyzedText::new(&text.key().replace('&', " and "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::normalize::Normalizer;
+
+    fn analyzed(analyzer: &dyn Analyzer, text: &str) -> String {
+        analyzer.analyze(AnalyzedText::new(text)).key()
+    }
+
+    #[test]
+    fn lowercase_folds_every_character() {
+        assert_eq!(analyzed(&Lowercase, "ÉCOLE Straße"), "école strasse");
+        assert_eq!(
+            analyzed(&Lowercase, "ﬁle ﬆ ŉ ǰ"),
+            "file st \u{2bc}n j\u{30c}"
+        );
+        assert_eq!(analyzed(&Lowercase, "µ ſ ᾈ İ ı"), "μ s ἀι i\u{307} ı");
+        assert_eq!(analyzed(&Lowercase, "Ꭰꭰᏸ"), "ᎠᎠᏰ");
+    }
+
+    #[test]
+    fn accent_folding_removes_marks() {
+        assert_eq!(analyzed(&AccentFolding, "Crème Brûlée"), "Creme Brulee");
+        assert_eq!(analyzed(&AccentFolding, "Cafe\u{301}"), "Cafe");
+    }
+
+    #[test]
+    fn punctuation_stripping_keeps_words() {
+        assert_eq!(
+            analyzed(&PunctuationStripping, "Don't—stop, me «now»!"),
+            "Dontstop me now"
+        );
+        assert_eq!(analyzed(&PunctuationStripping, "東京、大阪。"), "東京大阪");
+    }
+
+    #[test]
+    fn width_folding_folds_fullwidth_and_halfwidth_forms() {
+        assert_eq!(analyzed(&WidthFolding, "ＡＢＣ１２３　ｘ"), "ABC123 x");
+        assert_eq!(analyzed(&WidthFolding, "ｶﾀｶﾅ"), "カタカナ");
+
+        let chain = Chain::new().with(WidthFolding).with(Normalizer::new());
+        assert_eq!(analyzed(&chain, "ｶﾞｷﾞ"), "ガギ");
+    }
+
+    #[test]
+    fn origins_stay_within_the_original_text() {
+        let text = analyze(&AndExpansion, "R&D", false);
+
+        assert_eq!(text.key(), "R and D");
+        assert_eq!(text.origins(), &[0, 1, 2, 2, 2, 2, 2]);
+        assert_eq!(analyze(&AndExpansion, "", false), AnalyzedText::default());
+    }
+
+    #[test]
+    fn chain_keeps_origins() {
+        let chain = Chain::new().with(PunctuationStripping).with(Lowercase);
+        let text = chain.analyze(AnalyzedText::new("A-ẞ"));
+
+        assert_eq!(text.chars(), &['a', 's', 's']);
+        assert_eq!(text.origins(), &[0, 2, 2]);
+    }
+}
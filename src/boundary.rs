@@ -0,0 +1,401 @@
+use std::collections::HashSet;
+
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::analyzer::AnalyzedText;
+use crate::score_configuration::ScoreConfiguration;
+
+/// The word starts and ends of a phrase, split on the configured word delimiters and,
+/// when enabled, camelCase, letter to digit, acronym and script transitions.
+/// Also keeps the grapheme cluster starts, so matches never split a user perceived character.
+#[derive(Debug)]
+pub(crate) struct WordBoundaries {
+    starts: Vec<bool>,
+    ends: Vec<bool>,
+    graphemes: Vec<bool>,
+    /// characters belonging to a configured stop word
+    stop_words: Vec<bool>,
+    /// characters after the first inside a stop word of the phrase book, where no suffix is indexed
+    skipped: Vec<bool>,
+}
+
+impl WordBoundaries {
+    pub(crate) fn new(config: &ScoreConfiguration, phrase: &[char]) -> Self {
+        let is_delimiter = |i: usize| config.word_delimiters.contains(&phrase[i]);
+
+        let starts: Vec<bool> = (0..phrase.len())
+            .map(|i| {
+                !is_delimiter(i)
+                    && (i == 0 || is_delimiter(i - 1) || is_transition(config, phrase, i))
+            })
+            .collect();
+        let ends: Vec<bool> = (0..phrase.len())
+            .map(|i| {
+                !is_delimiter(i) && (i + 1 == phrase.len() || is_delimiter(i + 1) || starts[i + 1])
+            })
+            .collect();
+        let graphemes = grapheme_starts(&phrase.iter().collect::<String>());
+        let stop_words = mark_stop_words(&config.stop_words, phrase, &starts, &ends);
+
+        Self {
+            starts,
+            ends,
+            graphemes,
+            stop_words,
+            skipped: Vec::new(),
+        }
+    }
+
+    /// The word boundaries of the original phrase, moved onto its analyzed text.
+    /// A word starts at the first analyzed character at or after an original word start, and ends
+    /// at the last analyzed character at or before an original word end, so boundaries survive
+    /// analyzers which remove or expand characters. Runs may not start inside the skipped words
+    /// past the first analyzed character, like the suffixes of the phrase book.
+    pub(crate) fn analyzed(
+        config: &ScoreConfiguration,
+        skipped_words: Option<&HashSet<String>>,
+        phrase: &str,
+        text: &AnalyzedText,
+    ) -> Self {
+        let original: Vec<char> = phrase.chars().collect();
+        let boundaries = Self::new(config, &original);
+        let origins = text.origins();
+
+        let starts = (0..origins.len())
+            .map(|i| {
+                let from = match i {
+                    0 => 0,
+                    _ if origins[i - 1] == origins[i] => return false,
+                    _ => origins[i - 1] + 1,
+                };
+                (from..=origins[i]).any(|o| boundaries.is_start(o))
+            })
+            .collect();
+        let ends = (0..origins.len())
+            .map(|i| {
+                let to = match origins.get(i + 1) {
+                    None => original.len(),
+                    Some(&next) if next == origins[i] => return false,
+                    Some(&next) => next,
+                };
+                (origins[i]..to).any(|o| boundaries.is_end(o))
+            })
+            .collect();
+        let graphemes = grapheme_starts(&text.key());
+        let stop_words = origins
+            .iter()
+            .map(|&origin| boundaries.is_stop_word(origin))
+            .collect();
+        let skipped = match skipped_words {
+            Some(words) => {
+                let stopped = stopped(words, &original);
+                origins
+                    .iter()
+                    .enumerate()
+                    .map(|(i, &origin)| i > 0 && stopped.get(origin).copied().unwrap_or(false))
+                    .collect()
+            }
+            None => Vec::new(),
+        };
+
+        Self {
+            starts,
+            ends,
+            graphemes,
+            stop_words,
+            skipped,
+        }
+    }
+
+    /// Whether a word begins at the character position
+    pub(crate) fn is_start(&self, position: usize) -> bool {
+        self.starts.get(position).copied().unwrap_or(false)
+    }
+
+    /// Whether a word ends at the character position
+    pub(crate) fn is_end(&self, position: usize) -> bool {
+        self.ends.get(position).copied().unwrap_or(false)
+    }
+
+    /// Whether the character position is part of a stop word
+    pub(crate) fn is_stop_word(&self, position: usize) -> bool {
+        self.stop_words.get(position).copied().unwrap_or(false)
+    }
+
+    /// Character positions which begin a word
+    pub(crate) fn starts(&self) -> impl Iterator<Item = usize> + '_ {
+        self.starts
+            .iter()
+            .enumerate()
+            .filter_map(|(i, &start)| start.then_some(i))
+    }
+
+    /// Whether every run of adjacent positions begins at the start of a word
+    pub(crate) fn runs_start_at_words(&self, positions: &[usize]) -> bool {
+        positions.iter().enumerate().all(|(i, &position)| {
+            (i > 0 && positions[i - 1] + 1 == position) || self.is_start(position)
+        })
+    }
+
+    /// Whether every run of adjacent positions covers whole grapheme clusters, so that "e" does
+    /// not match the first half of "e\u{301}" and "👨" does not match part of "👨‍👩‍👧"
+    pub(crate) fn runs_cover_graphemes(&self, positions: &[usize]) -> bool {
+        let is_grapheme_start = |position: usize| self.graphemes.get(position) == Some(&true);
+        positions.iter().enumerate().all(|(i, &position)| {
+            let run_starts = i == 0 || positions[i - 1] + 1 != position;
+            let run_ends = positions.get(i + 1) != Some(&(position + 1));
+            (!run_starts || is_grapheme_start(position))
+                && (!run_ends || is_grapheme_start(position + 1))
+        })
+    }
+
+    /// Whether no run of adjacent positions starts inside a skipped word
+    pub(crate) fn runs_start_unskipped(&self, positions: &[usize]) -> bool {
+        positions.iter().enumerate().all(|(i, &position)| {
+            (i > 0 && positions[i - 1] + 1 == position)
+                || !self.skipped.get(position).copied().unwrap_or(false)
+        })
+    }
+}
+
+/// Marks the characters of every word which is one of the stop words, splitting words like the
+/// default [ScoreConfiguration], so the phrase book and the scorer agree on them
+pub(crate) fn stopped(stop_words: &HashSet<String>, phrase: &[char]) -> Vec<bool> {
+    if stop_words.is_empty() {
+        return vec![false; phrase.len()];
+    }
+    let boundaries = WordBoundaries::new(&ScoreConfiguration::default(), phrase);
+    mark_stop_words(stop_words, phrase, &boundaries.starts, &boundaries.ends)
+}
+
+/// Marks the characters of every word which is one of the stop words
+fn mark_stop_words(
+    words: &HashSet<String>,
+    phrase: &[char],
+    starts: &[bool],
+    ends: &[bool],
+) -> Vec<bool> {
+    let mut stop_words = vec![false; phrase.len()];
+    if words.is_empty() {
+        return stop_words;
+    }
+    for start in (0..phrase.len()).filter(|&i| starts[i]) {
+        let Some(end) = (start..phrase.len()).find(|&i| ends[i]) else {
+            continue;
+        };
+        let word: String = phrase[start..=end].iter().collect();
+        if words.contains(&word.to_lowercase()) {
+            stop_words[start..=end].fill(true);
+        }
+    }
+    stop_words
+}
+
+/// Whether every character position begins a grapheme cluster, with an extra entry marking the
+/// end of the text
+pub(crate) fn grapheme_starts(text: &str) -> Vec<bool> {
+    let mut starts = vec![false; text.chars().count() + 1];
+    let mut position = 0;
+    for grapheme in text.graphemes(true) {
+        starts[position] = true;
+        position += grapheme.chars().count();
+    }
+    starts[position] = true;
+    starts
+}
+
+/// Whether a word begins at `i` because of the character case or kind changing
+fn is_transition(config: &ScoreConfiguration, phrase: &[char], i: usize) -> bool {
+    let (previous, current) = (phrase[i - 1], phrase[i]);
+    let next = phrase.get(i + 1);
+
+    let camel_case = previous.is_lowercase() && current.is_uppercase();
+    let digit = (previous.is_alphabetic() && current.is_numeric())
+        || (previous.is_numeric() && current.is_alphabetic());
+    let acronym =
+        previous.is_uppercase() && current.is_uppercase() && next.is_some_and(|c| c.is_lowercase());
+
+    let script_change = matches!(
+        (script(previous), script(current)),
+        (Some(previous), Some(current)) if previous != current
+    );
+    let ideograph = script(current) == Some(Script::Han);
+
+    (config.camel_case_boundaries && camel_case)
+        || (config.digit_boundaries && digit)
+        || (config.acronym_boundaries && acronym)
+        || (config.script_boundaries && script_change)
+        || (config.ideograph_boundaries && ideograph)
+}
+
+/// Scripts written without spaces between words
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Script {
+    Han,
+    Hiragana,
+    Katakana,
+    Hangul,
+    Other,
+}
+
+/// The script of the character, `None` for marks such as `ー` and `々` which continue the
+/// script of the character before them
+fn script(c: char) -> Option<Script> {
+    match c {
+        '\u{3005}' | '\u{309D}' | '\u{309E}' | '\u{30FC}'..='\u{30FE}' | '\u{FF70}' => None,
+        '\u{3007}'
+        | '\u{3400}'..='\u{4DBF}'
+        | '\u{4E00}'..='\u{9FFF}'
+        | '\u{F900}'..='\u{FAFF}'
+        | '\u{20000}'..='\u{3134F}' => Some(Script::Han),
+        '\u{3041}'..='\u{309F}' => Some(Script::Hiragana),
+        '\u{30A0}'..='\u{30FF}' | '\u{31F0}'..='\u{31FF}' | '\u{FF66}'..='\u{FF9F}' => {
+            Some(Script::Katakana)
+        }
+        '\u{1100}'..='\u{11FF}' | '\u{3130}'..='\u{318F}' | '\u{AC00}'..='\u{D7AF}' => {
+            Some(Script::Hangul)
+        }
+        _ => Some(Script::Other),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn boundaries(phrase: &str) -> (Vec<usize>, Vec<usize>) {
+        boundaries_with(&ScoreConfiguration::default(), phrase)
+    }
+
+    fn boundaries_with(config: &ScoreConfiguration, phrase: &str) -> (Vec<usize>, Vec<usize>) {
+        let phrase: Vec<char> = phrase.chars().collect();
+        let boundaries = WordBoundaries::new(config, &phrase);
+        let ends = (0..phrase.len())
+            .filter(|&i| boundaries.is_end(i))
+            .collect();
+        (boundaries.starts().collect(), ends)
+    }
+
+    #[test]
+    fn delimiters_split_words() {
+        assert_eq!(boundaries("hello-world"), (vec![0, 6], vec![4, 10]));
+        assert_eq!(boundaries("a/b_c d"), (vec![0, 2, 4, 6], vec![0, 2, 4, 6]));
+    }
+
+    #[test]
+    fn repeated_delimiters_are_not_words() {
+        assert_eq!(boundaries("--hi  there-"), (vec![2, 6], vec![3, 10]));
+    }
+
+    #[test]
+    fn camel_case_and_digits_split_words() {
+        assert_eq!(
+            boundaries("parseHttpRequest2Json"),
+            (vec![0, 5, 9, 16, 17], vec![4, 8, 15, 16, 20])
+        );
+    }
+
+    #[test]
+    fn acronym_ends_split_words() {
+        assert_eq!(boundaries("HTTPServer"), (vec![0, 4], vec![3, 9]));
+        assert_eq!(boundaries("getHTTP"), (vec![0, 3], vec![2, 6]));
+    }
+
+    #[test]
+    fn analyzed_boundaries_follow_the_original_phrase() {
+        use crate::analyzer::{Analyzer, Chain, Lowercase, PunctuationStripping};
+
+        let config = ScoreConfiguration::default();
+        let analyzer = Chain::new().with(PunctuationStripping).with(Lowercase);
+        let phrase = "'quoted'-ẞtraßeHTTP";
+        let text = analyzer.analyze(AnalyzedText::new(phrase));
+        let boundaries = WordBoundaries::analyzed(&config, None, phrase, &text);
+        let ends: Vec<usize> = (0..text.chars().len())
+            .filter(|&i| boundaries.is_end(i))
+            .collect();
+
+        assert_eq!(
+            text.chars().iter().collect::<String>(),
+            "quotedsstrassehttp"
+        );
+        assert_eq!(boundaries.starts().collect::<Vec<_>>(), vec![0, 6, 14]);
+        assert_eq!(ends, vec![5, 13, 17]);
+    }
+
+    #[test]
+    fn script_changes_split_words() {
+        assert_eq!(boundaries("東京タワー"), (vec![0, 2], vec![1, 4]));
+        assert_eq!(
+            boundaries("すしロボット2号"),
+            (vec![0, 2, 6, 7], vec![1, 5, 6, 7])
+        );
+        assert_eq!(boundaries("らーめん々"), (vec![0], vec![4]));
+        assert_eq!(
+            boundaries("iPhone用ケース"),
+            (vec![0, 1, 6, 7], vec![0, 5, 6, 9])
+        );
+    }
+
+    #[test]
+    fn ideographs_can_be_words() {
+        let config = ScoreConfiguration::builder()
+            .with_ideograph_boundaries(true)
+            .build();
+        assert_eq!(
+            boundaries_with(&config, "北京大学 ok"),
+            (vec![0, 1, 2, 3, 5], vec![0, 1, 2, 3, 6])
+        );
+        assert_eq!(boundaries("北京大学"), (vec![0], vec![3]));
+    }
+
+    #[test]
+    fn stop_words_are_marked() {
+        let config = ScoreConfiguration::builder()
+            .with_stop_words(["the".to_string(), "of".to_string()].into())
+            .build();
+        let phrase: Vec<char> = "The theory of-everything".chars().collect();
+        let boundaries = WordBoundaries::new(&config, &phrase);
+        let stop_words: Vec<usize> = (0..phrase.len())
+            .filter(|&i| boundaries.is_stop_word(i))
+            .collect();
+
+        assert_eq!(stop_words, vec![0, 1, 2, 11, 12]);
+    }
+
+    #[test]
+    fn grapheme_clusters_are_not_split() {
+        let phrase: Vec<char> = "qe\u{301}👨‍👩‍👧🇯🇵".chars().collect();
+        let boundaries = WordBoundaries::new(&ScoreConfiguration::default(), &phrase);
+
+        assert!(boundaries.runs_cover_graphemes(&[0]));
+        assert!(boundaries.runs_cover_graphemes(&[0, 1, 2]));
+        assert!(!boundaries.runs_cover_graphemes(&[1]));
+        assert!(!boundaries.runs_cover_graphemes(&[2]));
+        assert!(boundaries.runs_cover_graphemes(&[3, 4, 5, 6, 7]));
+        assert!(!boundaries.runs_cover_graphemes(&[3]));
+        assert!(!boundaries.runs_cover_graphemes(&[8]));
+        assert!(boundaries.runs_cover_graphemes(&[0, 8, 9]));
+    }
+
+    #[test]
+    fn transition_rules_can_be_disabled() {
+        let config = ScoreConfiguration::builder()
+            .with_camel_case_boundaries(false)
+            .with_digit_boundaries(false)
+            .with_acronym_boundaries(false)
+            .with_script_boundaries(false)
+            .build();
+        assert_eq!(
+            boundaries_with(&config, "parseHTTPServer2Json"),
+            (vec![0], vec![19])
+        );
+
+        let config = ScoreConfiguration::builder()
+            .with_camel_case_boundaries(false)
+            .build();
+        assert_eq!(
+            boundaries_with(&config, "parseHTTPServer2Json"),
+            (vec![0, 9, 15, 16], vec![8, 14, 15, 19])
+        );
+    }
+}
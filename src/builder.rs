@@ -1,21 +1,32 @@
+use crate::alias::Aliases;
+use crate::frecency::Frecency;
 use crate::score_configuration::ScoreConfiguration;
+use crate::selection::SelectionMemory;
 use crate::{TrieNode, YouAutoCompleteMe};
 
 /// Builder for [YouAutoCompleteMe]
-pub struct YouAutoCompleteMeBuilder<'a> {
+pub struct YouAutoCompleteMeBuilder<'a, P = ()> {
     // match_configuration: Option<MatchConfiguration>,
     score_configuration: Option<ScoreConfiguration>,
-    phrase_book: &'a TrieNode,
+    phrase_book: &'a TrieNode<P>,
+    aliases: Option<&'a Aliases>,
+    frecency: Option<&'a Frecency>,
+    selection_memory: Option<&'a SelectionMemory>,
+    grouping: Option<bool>,
 }
 
-impl<'a> YouAutoCompleteMeBuilder<'a> {
+impl<'a, P> YouAutoCompleteMeBuilder<'a, P> {
     /// Create a new [YouAutoCompleteMeBuilder] with a Phrasebook
     ///warning: the TrieNode as a public interface is going away
-    pub fn new(phrase_book: &'a TrieNode) -> Self {
+    pub fn new(phrase_book: &'a TrieNode<P>) -> Self {
         Self {
             // match_configuration: None,
             score_configuration: None,
             phrase_book,
+            aliases: None,
+            frecency: None,
+            selection_memory: None,
+            grouping: None,
         }
     }
 
@@ -34,12 +45,43 @@ impl<'a> YouAutoCompleteMeBuilder<'a> {
         self
     }
 
+    /// the Created [YouAutoCompleteMe] expands searches using the [Aliases]
+    pub fn with_aliases(mut self, aliases: &'a Aliases) -> Self {
+        self.aliases = Some(aliases);
+        self
+    }
+
+    /// the Created [YouAutoCompleteMe] ranks frequently and recently selected phrases higher
+    /// with the [Frecency], and records selections into it
+    pub fn with_frecency(mut self, frecency: &'a Frecency) -> Self {
+        self.frecency = Some(frecency);
+        self
+    }
+
+    /// the Created [YouAutoCompleteMe] ranks the phrase last selected for a query first with the
+    /// [SelectionMemory], and records selections into it
+    pub fn with_selection_memory(mut self, selection_memory: &'a SelectionMemory) -> Self {
+        self.selection_memory = Some(selection_memory);
+        self
+    }
+
+    /// the Created [YouAutoCompleteMe] collapses matches of the same
+    /// [group](TrieNode::group) into the best scoring one
+    pub fn with_grouping(mut self, grouping: bool) -> Self {
+        self.grouping = Some(grouping);
+        self
+    }
+
     /// Build the [YouAutoCompleteMe]
-    pub fn build(self) -> YouAutoCompleteMe<'a> {
+    pub fn build(self) -> YouAutoCompleteMe<'a, P> {
         YouAutoCompleteMe {
             // match_configuration: self.match_configuration.unwrap_or_default(),
             score_configuration: self.score_configuration.unwrap_or_default(),
             phrase_book: self.phrase_book,
+            aliases: self.aliases,
+            frecency: self.frecency,
+            selection_memory: self.selection_memory,
+            grouping: self.grouping.unwrap_or(false),
         }
     }
 }
@@ -0,0 +1,139 @@
+//! # Cancel Module
+//!
+//! Interrupts searches which are no longer needed, such as the search for the previous
+//! keystroke in an interactive picker, or which run past a deadline. Searches check their
+//! [CancellationToken] and deadline while traversing the phrase book and scoring candidates,
+//! and return the matches scored so far flagged as
+//! [incomplete](crate::SearchResults::incomplete).
+//!
+//! ## Example
+//!
+//! ```
+//! use std::time::{Duration, Instant};
+//! use you_autocomplete_me::cancel::CancellationToken;
+//! use you_autocomplete_me::search::Search;
+//! use you_autocomplete_me::{TrieNode, YouAutoCompleteMe};
+//! let mut phrase_book = TrieNode::default();
+//! phrase_book.insert("hello");
+//! let completer = YouAutoCompleteMe::new(&phrase_book);
+//!
+//! let token = CancellationToken::new();
+//! let search = Search::builder()
+//!     .search("hel")
+//!     .cancellation(token.clone())
+//!     .deadline(Instant::now() + Duration::from_secs(60))
+//!     .build();
+//! let results = completer.search_results(&search);
+//! assert!(!results.incomplete);
+//! assert_eq!(results.matches.len(), 1);
+//!
+//! token.cancel();
+//! let results = completer.search_results(&search);
+//! assert!(results.incomplete);
+//! assert!(results.matches.is_empty());
+//! ```
+
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+
+use crate::search::Search;
+
+/// How many checks pass between readings of the clock
+const CLOCK_INTERVAL: u32 = 64;
+
+/// A flag shared between a search and whoever may cancel it. Clones share the flag.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    /// Creates a token which is not cancelled
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Cancels every search holding the token
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// Whether the token was cancelled
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// The cancellation token and deadline of a search, checked cooperatively
+#[derive(Debug, Default)]
+pub(crate) struct Interrupt<'s> {
+    cancellation: Option<&'s CancellationToken>,
+    deadline: Option<Instant>,
+    checks: AtomicU32,
+    interrupted: AtomicBool,
+}
+
+impl<'s> Interrupt<'s> {
+    pub(crate) fn new(search: &'s Search<'_>) -> Self {
+        Self {
+            cancellation: search.cancellation.as_ref(),
+            deadline: search.deadline,
+            ..Default::default()
+        }
+    }
+
+    /// An interrupt which never fires
+    pub(crate) fn never() -> Self {
+        Default::default()
+    }
+
+    /// Whether the search should stop. Once interrupted, it stays interrupted.
+    pub(crate) fn check(&self) -> bool {
+        if self.interrupted() {
+            return true;
+        }
+        let cancelled = self
+            .cancellation
+            .is_some_and(CancellationToken::is_cancelled);
+        let checks = self.checks.fetch_add(1, Ordering::Relaxed);
+        let expired = checks.is_multiple_of(CLOCK_INTERVAL)
+            && self
+                .deadline
+                .is_some_and(|deadline| Instant::now() >= deadline);
+        if cancelled || expired {
+            self.interrupted.store(true, Ordering::Relaxed);
+        }
+        self.interrupted()
+    }
+
+    /// Whether any check found the search interrupted
+    pub(crate) fn interrupted(&self) -> bool {
+        self.interrupted.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interrupts_stay_interrupted() {
+        let token = CancellationToken::new();
+        let search = Search::builder()
+            .search("a")
+            .cancellation(token.clone())
+            .build();
+        let interrupt = Interrupt::new(&search);
+        assert!(!interrupt.check());
+
+        token.cancel();
+        assert!(interrupt.check());
+        assert!(interrupt.interrupted());
+
+        let search = Search::builder()
+            .search("a")
+            .deadline(Instant::now())
+            .build();
+        assert!(Interrupt::new(&search).check());
+        assert!(!Interrupt::never().check());
+    }
+}
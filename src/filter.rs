@@ -0,0 +1,172 @@
+//! # Filter Module
+//!
+//! Filters completions by the metadata attached to phrases with
+//! [TrieNode::insert_with_metadata](crate::TrieNode::insert_with_metadata). A filter on a
+//! [Search](crate::search::Search) is applied while the phrase book is traversed, so phrases
+//! it rejects are never collected or scored. Phrases without the filtered attribute never
+//! match.
+//!
+//! ## Example
+//!
+//! ```
+//! use you_autocomplete_me::filter::{Filter, Value};
+//! use you_autocomplete_me::search::Search;
+//! use you_autocomplete_me::{TrieNode, YouAutoCompleteMe};
+//! let mut phrase_book = TrieNode::default();
+//! phrase_book.insert_with_metadata(
+//!     "readme.md",
+//!     [("kind", Value::from("file")), ("size", Value::from(120))],
+//! );
+//! phrase_book.insert_with_metadata(
+//!     "reader",
+//!     [("kind", Value::from("user")), ("lang", Value::from("en"))],
+//! );
+//! let completer = YouAutoCompleteMe::new(&phrase_book);
+//!
+//! let search = Search::builder()
+//!     .search("read")
+//!     .filter(Filter::is_in("kind", ["file", "dir"]).and(Filter::range("size", ..1_000.0)))
+//!     .build();
+//! let matches = completer.search(&search);
+//! assert_eq!(matches.len(), 1);
+//! assert_eq!(matches[0].phrase, "readme.md");
+//!
+//! let search = Search::builder().search("read").filter(Filter::eq("lang", "en")).build();
+//! assert_eq!(completer.search(&search)[0].phrase, "reader");
+//! ```
+
+use std::collections::HashMap;
+use std::ops::{Bound, RangeBounds};
+
+/// The attributes of a phrase
+pub type Metadata = HashMap<String, Value>;
+
+/// The value of an attribute
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    /// A textual value, compared exactly
+    Text(String),
+    /// A numeric value
+    Number(f64),
+}
+
+impl From<&str> for Value {
+    fn from(value: &str) -> Self {
+        Value::Text(value.to_string())
+    }
+}
+
+impl From<String> for Value {
+    fn from(value: String) -> Self {
+        Value::Text(value)
+    }
+}
+
+impl From<f64> for Value {
+    fn from(value: f64) -> Self {
+        Value::Number(value)
+    }
+}
+
+impl From<i64> for Value {
+    fn from(value: i64) -> Self {
+        Value::Number(value as f64)
+    }
+}
+
+impl From<i32> for Value {
+    fn from(value: i32) -> Self {
+        Value::Number(f64::from(value))
+    }
+}
+
+/// A predicate on the metadata of a phrase
+#[derive(Debug, Clone, PartialEq)]
+pub enum Filter {
+    /// The attribute equals the value
+    Eq(String, Value),
+    /// The attribute equals any of the values
+    In(String, Vec<Value>),
+    /// The attribute is a number within the bounds
+    Range(String, Bound<f64>, Bound<f64>),
+    /// Every filter matches
+    All(Vec<Filter>),
+}
+
+impl Filter {
+    /// The attribute equals the value
+    pub fn eq(attribute: &str, value: impl Into<Value>) -> Self {
+        Filter::Eq(attribute.to_string(), value.into())
+    }
+
+    /// The attribute equals any of the values
+    pub fn is_in<V: Into<Value>>(attribute: &str, values: impl IntoIterator<Item = V>) -> Self {
+        Filter::In(
+            attribute.to_string(),
+            values.into_iter().map(Into::into).collect(),
+        )
+    }
+
+    /// The attribute is a number within the range
+    pub fn range(attribute: &str, range: impl RangeBounds<f64>) -> Self {
+        Filter::Range(
+            attribute.to_string(),
+            range.start_bound().cloned(),
+            range.end_bound().cloned(),
+        )
+    }
+
+    /// Both this and the other filter match
+    pub fn and(self, other: Filter) -> Self {
+        match self {
+            Filter::All(mut filters) => {
+                filters.push(other);
+                Filter::All(filters)
+            }
+            filter => Filter::All(vec![filter, other]),
+        }
+    }
+
+    /// Whether the metadata matches the filter
+    pub fn matches(&self, metadata: Option<&Metadata>) -> bool {
+        let value = |attribute: &str| metadata.and_then(|metadata| metadata.get(attribute));
+        match self {
+            Filter::Eq(attribute, expected) => value(attribute) == Some(expected),
+            Filter::In(attribute, values) => {
+                value(attribute).is_some_and(|value| values.contains(value))
+            }
+            Filter::Range(attribute, start, end) => match value(attribute) {
+                Some(Value::Number(number)) => (*start, *end).contains(number),
+                _ => false,
+            },
+            Filter::All(filters) => filters.iter().all(|filter| filter.matches(metadata)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn filters_match_metadata() {
+        let metadata: Metadata = [
+            ("lang".to_string(), Value::from("en")),
+            ("size".to_string(), Value::from(10)),
+        ]
+        .into();
+        let metadata = Some(&metadata);
+
+        assert!(Filter::eq("lang", "en").matches(metadata));
+        assert!(!Filter::eq("lang", "fr").matches(metadata));
+        assert!(!Filter::eq("kind", "file").matches(metadata));
+        assert!(Filter::is_in("lang", ["fr", "en"]).matches(metadata));
+        assert!(Filter::range("size", 10.0..=10.0).matches(metadata));
+        assert!(!Filter::range("size", ..10.0).matches(metadata));
+        assert!(!Filter::range("lang", ..).matches(metadata));
+        assert!(Filter::eq("lang", "en")
+            .and(Filter::range("size", 5.0..))
+            .matches(metadata));
+        assert!(!Filter::eq("lang", "en").matches(None));
+    }
+}
@@ -0,0 +1,240 @@
+//! # Frecency Module
+//!
+//! Learns from the phrases users select. Every selection adds one to the phrase's frecency,
+//! which then decays exponentially, halving every half-life. Phrases selected often and
+//! recently rank higher, blended into the score by the
+//! [frecency weight](crate::ScoreConfigurationBuilder::with_frecency_weight).
+//!
+//! ## Example
+//!
+//! ```
+//! use you_autocomplete_me::frecency::Frecency;
+//! use you_autocomplete_me::{Completer, TrieNode, YouAutoCompleteMe};
+//! let mut phrase_book = TrieNode::default();
+//! phrase_book.insert("production-east");
+//! phrase_book.insert("production-west");
+//! let frecency = Frecency::new();
+//! let completer = YouAutoCompleteMe::builder(&phrase_book)
+//!     .with_frecency(&frecency)
+//!     .build();
+//! assert_eq!(completer.complete("prod")[0].phrase, "production-east");
+//!
+//! completer.record_selection("prod", "production-west");
+//! assert_eq!(completer.complete("prod")[0].phrase, "production-west");
+//!
+//! // frecency survives restarts by saving and loading it
+//! let mut saved = Vec::new();
+//! frecency.save(&mut saved).unwrap();
+//! let restored = Frecency::new().load(saved.as_slice()).unwrap();
+//! assert!(restored.frecency("production-west") > 0.0);
+//! ```
+
+use std::collections::HashMap;
+use std::io::{self, BufRead, Write};
+use std::sync::RwLock;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const DEFAULT_HALF_LIFE: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// The frecency of selected phrases. Selections are recorded through a shared reference, so
+/// that a [YouAutoCompleteMe](crate::YouAutoCompleteMe) can rank with it while it learns.
+#[derive(Debug)]
+pub struct Frecency {
+    half_life: Duration,
+    /// The frecency of each phrase as of the second it was last selected
+    entries: RwLock<HashMap<String, Entry>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Entry {
+    frecency: f64,
+    /// seconds since the unix epoch
+    selected: f64,
+}
+
+impl Default for Frecency {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Frecency {
+    /// Creates a frecency without selections, halving every week
+    pub fn new() -> Self {
+        Self {
+            half_life: DEFAULT_HALF_LIFE,
+            entries: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Sets how long it takes the frecency of a selection to halve
+    pub fn with_half_life(mut self, half_life: Duration) -> Self {
+        self.half_life = half_life;
+        self
+    }
+
+    /// Records that the phrase was selected now
+    pub fn record(&self, phrase: &str) {
+        self.record_at(phrase, SystemTime::now());
+    }
+
+    /// Records that the phrase was selected at the given time
+    pub fn record_at(&self, phrase: &str, at: SystemTime) {
+        let at = seconds(at);
+        let mut entries = self.entries.write().unwrap();
+        let decayed = entries
+            .get(phrase)
+            .map_or(0.0, |entry| self.decay(entry, at));
+        entries.insert(
+            phrase.to_string(),
+            Entry {
+                frecency: decayed + 1.0,
+                selected: at,
+            },
+        );
+    }
+
+    /// The frecency of the phrase now, zero if it was never selected
+    pub fn frecency(&self, phrase: &str) -> f64 {
+        self.frecency_at(phrase, SystemTime::now())
+    }
+
+    /// The frecency of the phrase at the given time, zero if it was never selected
+    pub fn frecency_at(&self, phrase: &str, at: SystemTime) -> f64 {
+        self.entries
+            .read()
+            .unwrap()
+            .get(phrase)
+            .map_or(0.0, |entry| self.decay(entry, seconds(at)))
+    }
+
+    /// The score bonus of the phrase for the given weight
+    pub(crate) fn boost(&self, phrase: &str, weight: u8) -> i32 {
+        (f64::from(weight) * self.frecency(phrase).ln_1p()).round() as i32
+    }
+
+    fn decay(&self, entry: &Entry, at: f64) -> f64 {
+        let elapsed = (at - entry.selected).max(0.0);
+        let half_lives = elapsed / self.half_life.as_secs_f64().max(f64::MIN_POSITIVE);
+        entry.frecency * 0.5f64.powf(half_lives)
+    }
+
+    /// Writes every selected phrase, one per line, as its frecency, the time it was last
+    /// selected and the phrase itself, separated by tabs
+    pub fn save(&self, mut writer: impl Write) -> io::Result<()> {
+        let entries = self.entries.read().unwrap();
+        let mut phrases: Vec<_> = entries.iter().collect();
+        phrases.sort_by(|a, b| a.0.cmp(b.0));
+        for (phrase, entry) in phrases {
+            writeln!(
+                writer,
+                "{}\t{}\t{}",
+                entry.frecency,
+                entry.selected,
+                escape(phrase)
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Reads phrases written by [save](Frecency::save), adding them to the selections
+    pub fn load(self, reader: impl BufRead) -> io::Result<Self> {
+        {
+            let mut entries = self.entries.write().unwrap();
+            for line in reader.lines() {
+                let line = line?;
+                if line.is_empty() {
+                    continue;
+                }
+                let invalid = || io::Error::new(io::ErrorKind::InvalidData, line.clone());
+                let mut fields = line.splitn(3, '\t');
+                let mut number = || -> io::Result<f64> {
+                    fields
+                        .next()
+                        .and_then(|field| field.parse().ok())
+                        .ok_or_else(invalid)
+                };
+                let entry = Entry {
+                    frecency: number()?,
+                    selected: number()?,
+                };
+                let phrase = fields.next().map(unescape).ok_or_else(invalid)?;
+                entries.insert(phrase, entry);
+            }
+        }
+        Ok(self)
+    }
+}
+
+fn seconds(at: SystemTime) -> f64 {
+    at.duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs_f64()
+}
+
+fn escape(phrase: &str) -> String {
+    phrase
+        .replace('\\', "\\\\")
+        .replace('\t', "\\t")
+        .replace('\n', "\\n")
+}
+
+fn unescape(phrase: &str) -> String {
+    let mut unescaped = String::with_capacity(phrase.len());
+    let mut chars = phrase.chars();
+    while let Some(c) = chars.next() {
+        unescaped.push(match c {
+            '\\' => match chars.next() {
+                Some('t') => '\t',
+                Some('n') => '\n',
+                Some(escaped) => escaped,
+                None => '\\',
+            },
+            c => c,
+        });
+    }
+    unescaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frecency_decays_by_half_every_half_life() {
+        let frecency = Frecency::new().with_half_life(Duration::from_secs(100));
+        let start = UNIX_EPOCH + Duration::from_secs(1_000);
+
+        frecency.record_at("a", start);
+        frecency.record_at("a", start);
+        assert_eq!(frecency.frecency_at("a", start), 2.0);
+        assert_eq!(
+            frecency.frecency_at("a", start + Duration::from_secs(100)),
+            1.0
+        );
+
+        frecency.record_at("a", start + Duration::from_secs(200));
+        assert_eq!(
+            frecency.frecency_at("a", start + Duration::from_secs(200)),
+            1.5
+        );
+        assert_eq!(frecency.frecency_at("b", start), 0.0);
+    }
+
+    #[test]
+    fn save_and_load_round_trip() {
+        let frecency = Frecency::new();
+        let at = UNIX_EPOCH + Duration::from_secs(1_000);
+        frecency.record_at("tab\tand\\slash", at);
+        frecency.record_at("new\nline", at);
+        frecency.record_at("new\nline", at);
+
+        let mut saved = Vec::new();
+        frecency.save(&mut saved).unwrap();
+        let loaded = Frecency::new().load(saved.as_slice()).unwrap();
+
+        assert_eq!(loaded.frecency_at("tab\tand\\slash", at), 1.0);
+        assert_eq!(loaded.frecency_at("new\nline", at), 2.0);
+        assert!(Frecency::new().load("oops".as_bytes()).is_err());
+    }
+}
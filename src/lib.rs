@@ -1,22 +1,54 @@
 //! # YouAutoCompleteMe
-//! 
+//!
 //! A Fuzzy Finder library in Rust
 
 #![allow(dead_code)]
 #![warn(missing_docs)]
 
+pub mod alias;
+pub mod analyzer;
+mod boundary;
 mod builder;
+pub mod cancel;
 mod edit_distance;
+pub mod filter;
+pub mod frecency;
 mod r#match;
-mod match_profile;
+pub mod namespace;
+pub mod normalize;
+pub mod page;
+pub mod phonetic;
+pub mod prediction;
+pub mod query;
+pub mod score;
 mod score_configuration;
 pub mod search;
+pub mod selection;
+pub mod session;
+pub mod transliterate;
 mod trie;
 
 pub use self::builder::YouAutoCompleteMeBuilder;
-pub use self::score_configuration::ScoreConfiguration;
+pub use self::r#match::{Match, SearchResults};
+pub use self::score_configuration::{ScoreConfiguration, ScoreConfigurationBuilder};
 pub use self::trie::TrieNode;
 
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+use self::alias::Aliases;
+use self::cancel::Interrupt;
+use self::frecency::Frecency;
+use self::page::Page;
+use self::prediction::Prediction;
+use self::query::Query;
+use self::score::{ScoreExplanation, Scorer};
+use self::search::Search;
+use self::selection::SelectionMemory;
+
 /// Matches phrases against the input and then scores them by relevancy
 /// ```
 /// use you_autocomplete_me::YouAutoCompleteMe;
@@ -27,33 +59,303 @@ pub use self::trie::TrieNode;
 /// let completer = YouAutoCompleteMe::builder(&phrasebook).build();
 /// ```
 #[derive(Debug)]
-pub struct YouAutoCompleteMe<'a> {
+pub struct YouAutoCompleteMe<'a, P = ()> {
     /// The configuration for the scoring algorithm
     score_configuration: ScoreConfiguration,
     /// Phrasebook
-    phrase_book: &'a TrieNode,
+    phrase_book: &'a TrieNode<P>,
+    /// Aliases expanding the search before the phrase book is consulted
+    aliases: Option<&'a Aliases>,
+    /// Frecency of selected phrases blended into the scores
+    frecency: Option<&'a Frecency>,
+    /// The phrase last selected for each query
+    selection_memory: Option<&'a SelectionMemory>,
+    /// Whether matches of the same group collapse into the best one
+    grouping: bool,
 }
 
-impl<'a> YouAutoCompleteMe<'a> {
+impl<'a, P> YouAutoCompleteMe<'a, P> {
     /// create a new instance of the auto completer
-    pub fn new(phrase_book: &'a TrieNode) -> Self {
+    pub fn new(phrase_book: &'a TrieNode<P>) -> Self {
         Self {
             phrase_book,
             score_configuration: Default::default(),
+            aliases: None,
+            frecency: None,
+            selection_memory: None,
+            grouping: false,
         }
     }
 
     /// Creates a [`YouAutoCompleteMeBuilder`] for configuring the auto completer
     /// warning: the phrasebook will go away in future builds
-    pub fn builder(phrase_book: &'a TrieNode) -> YouAutoCompleteMeBuilder<'a> {
+    pub fn builder(phrase_book: &'a TrieNode<P>) -> YouAutoCompleteMeBuilder<'a, P> {
         YouAutoCompleteMeBuilder::new(phrase_book)
     }
+
+    /// Explains how a phrase scores against a query, or `None` if the phrase does not match
+    /// ```
+    /// use you_autocomplete_me::{TrieNode, YouAutoCompleteMe};
+    /// let phrase_book = TrieNode::default();
+    /// let completer = YouAutoCompleteMe::new(&phrase_book);
+    ///
+    /// let explanation = completer.explain("wor", "hello-world").unwrap();
+    /// println!("{explanation}");
+    /// ```
+    pub fn explain<'q>(
+        &self,
+        query: impl Into<Search<'q>>,
+        phrase: &str,
+    ) -> Option<ScoreExplanation> {
+        let search = query.into();
+        let query = search.extended.then(|| Query::parse(&search));
+        self.scorer().explain(&search, query.as_ref(), phrase)
+    }
+
+    fn scorer(&self) -> Scorer<'_, 'a> {
+        Scorer {
+            config: &self.score_configuration,
+            analysis: self.phrase_book.analysis(),
+            records: self.phrase_book.records(),
+        }
+    }
+
+    /// Phrases matching the search, ordered from the highest score to the lowest
+    /// ```
+    /// use you_autocomplete_me::search::Search;
+    /// use you_autocomplete_me::{TrieNode, YouAutoCompleteMe};
+    /// let mut phrase_book = TrieNode::new();
+    /// phrase_book.insert_with_payload("hello-world", 1);
+    /// phrase_book.insert_with_payload("World", 2);
+    /// let completer = YouAutoCompleteMe::new(&phrase_book);
+    ///
+    /// let matches = completer.search(&Search::new("Wor"));
+    /// assert_eq!(matches.len(), 1);
+    /// assert_eq!(matches[0].phrase, "World");
+    /// assert_eq!(matches[0].payload, Some(&2));
+    /// assert!(matches[0].case_sensitive);
+    /// ```
+    pub fn search(&self, search: &Search<'_>) -> Vec<Match<'a, P>> {
+        self.search_results(search).matches
+    }
+
+    /// Phrases matching the search like [search](YouAutoCompleteMe::search), stopping early
+    /// when the search is cancelled or its deadline passes, see [cancel]
+    pub fn search_results(&self, search: &Search<'_>) -> SearchResults<'a, P> {
+        let interrupt = Interrupt::new(search);
+        let mut matches = self.matches(search, &interrupt);
+        #[cfg(feature = "rayon")]
+        matches.par_sort_by(Match::ranking);
+        #[cfg(not(feature = "rayon"))]
+        matches.sort_by(Match::ranking);
+
+        SearchResults {
+            matches: self.with_payloads(matches),
+            incomplete: interrupt.interrupted(),
+        }
+    }
+
+    /// A page of up to `limit` matches of the search after skipping `offset` of them, with a
+    /// [Cursor](page::Cursor) resuming after the page, see [page]
+    pub fn search_page(&self, search: &Search<'_>, offset: usize, limit: usize) -> Page<'a, P> {
+        let interrupt = Interrupt::new(search);
+        let matches = self.matches(search, &interrupt);
+        let results = SearchResults {
+            matches: self.with_payloads(matches),
+            incomplete: interrupt.interrupted(),
+        };
+        Page::new(results, offset, limit)
+    }
+
+    /// The scored matches of the search, in no particular order
+    fn matches(&self, search: &Search<'_>, interrupt: &Interrupt<'_>) -> Vec<Match<'a>> {
+        let expansions = match self.aliases {
+            Some(aliases) => aliases.expand(search.search),
+            None => vec![(search.search.to_string(), None)],
+        };
+
+        let mut found: Vec<Match<'a>> = Vec::new();
+        for (expansion, alias) in &expansions {
+            let expanded = Search {
+                search: expansion,
+                ..search.clone()
+            };
+            let query = expanded.extended.then(|| Query::parse(&expanded));
+            found.extend(self.search_query(&expanded, query.as_ref(), *alias, interrupt));
+        }
+        // expand the tokens of the search on their own, as alternatives within the query
+        if let Some(aliases) = self.aliases {
+            let query = match search.extended {
+                true => Query::parse(search),
+                false => Query::tokens(search),
+            };
+            if query.groups().len() > 1 {
+                if let Some(query) = aliases.expand_query(query) {
+                    found.extend(self.search_query(search, Some(&query), None, interrupt));
+                }
+            }
+        }
+
+        // keep the best match of every phrase across the expansions of the search
+        let mut best: HashMap<&'a str, Match<'a>> = HashMap::new();
+        for found in found {
+            match best.get(found.phrase) {
+                Some(existing) if existing.ranking(&found).is_le() => {}
+                _ => {
+                    best.insert(found.phrase, found);
+                }
+            }
+        }
+
+        let mut matches: Vec<_> = best.into_values().collect();
+        if let Some(frecency) = self.frecency {
+            let weight = self.score_configuration.frecency_weight;
+            for found in &mut matches {
+                found.score += frecency.boost(found.phrase, weight);
+            }
+        }
+        if let Some(selection) = self
+            .selection_memory
+            .and_then(|memory| memory.selection(search.search))
+        {
+            let bonus = i32::from(self.score_configuration.selection_bonus);
+            for found in matches.iter_mut().filter(|m| m.phrase == selection) {
+                found.score += bonus;
+            }
+        }
+        if self.grouping {
+            matches = self.best_of_groups(matches);
+        }
+        matches
+    }
+
+    /// The matches with the payloads attached to their phrases
+    fn with_payloads(&self, matches: Vec<Match<'a>>) -> Vec<Match<'a, P>> {
+        matches
+            .into_iter()
+            .map(|found| {
+                let payload = self.phrase_book.payload(found.phrase);
+                found.with_payload(payload)
+            })
+            .collect()
+    }
+
+    /// The best scoring match of every group, ties keeping the first phrase
+    fn best_of_groups(&self, matches: Vec<Match<'a>>) -> Vec<Match<'a>> {
+        let mut best: HashMap<String, Match<'a>> = HashMap::new();
+        for found in matches {
+            match best.entry(self.phrase_book.group(found.phrase)) {
+                Entry::Occupied(mut existing) => {
+                    let existing = existing.get_mut();
+                    if found.ranking(existing).is_lt() {
+                        *existing = found;
+                    }
+                }
+                Entry::Vacant(vacant) => {
+                    vacant.insert(found);
+                }
+            }
+        }
+        best.into_values().collect()
+    }
+
+    /// Words predicted to follow the input, best first, learned from the phrase book when it
+    /// has [next word prediction](TrieNode::with_next_word_prediction) enabled
+    pub fn predict(&self, input: &str) -> Vec<Prediction<'a>> {
+        self.phrase_book.predict(input)
+    }
+
+    /// Records that the phrase was selected after searching for the query, so that it ranks
+    /// higher in later searches. Does nothing without a [Frecency] or [SelectionMemory].
+    pub fn record_selection(&self, query: &str, phrase: &str) {
+        if let Some(memory) = self.selection_memory {
+            memory.record(query, phrase);
+        }
+        if let Some(frecency) = self.frecency {
+            frecency.record(phrase);
+        }
+    }
+
+    /// Matches of the search, or of the query when given, reporting the alias which expanded
+    /// the search or the first alias of the query terms which matched
+    fn search_query(
+        &self,
+        search: &Search<'_>,
+        query: Option<&Query<'_>>,
+        alias: Option<&'a str>,
+        interrupt: &Interrupt<'_>,
+    ) -> Vec<Match<'a>> {
+        // queries may match across word boundaries and in any order, so gather every phrase
+        // containing the first character of the first token
+        let first = search.search.split_whitespace().next();
+        let filter = search.filter.as_deref();
+        let mut candidates = match (query, first) {
+            (Some(query), _) => query.candidates(self.phrase_book, interrupt),
+            (None, Some(first)) => self
+                .phrase_book
+                .first_character_search(first, filter, interrupt),
+            (None, None) => self.phrase_book.interruptible_search("", filter, interrupt),
+        };
+        if search.phonetic {
+            candidates.extend(
+                self.phrase_book
+                    .phonetic_search(search.search)
+                    .into_iter()
+                    .filter(|phrase| {
+                        filter.is_none_or(|f| f.matches(self.phrase_book.metadata(phrase)))
+                    }),
+            );
+        }
+
+        let case_sensitive = search.strict_case || search.is_smart_case();
+        self.scorer()
+            .explain_candidates(search, query, candidates, interrupt)
+            .into_iter()
+            .map(|(phrase, explanation, field)| Match {
+                phrase,
+                score: explanation.score,
+                positions: explanation.positions,
+                case_sensitive,
+                phonetic: explanation.phonetic,
+                payload: None,
+                alias: alias.or_else(|| {
+                    let name = explanation.alias.as_deref()?;
+                    self.aliases?.get(name).map(|(alias, _)| alias)
+                }),
+                namespace: None,
+                field: field.map(|(name, _)| name.as_str()),
+                text: field.map_or(phrase, |(_, text)| text.as_str()),
+            })
+            .collect()
+    }
 }
 
 /// A trait use to complete from an input
-trait Completer {
+pub trait Completer {
+    /// The payload attached to completed phrases
+    type Payload;
+
     /// Completes a phrase
-    fn complete(&self, input: &str) -> Vec<String>;
+    fn complete(&self, input: &str) -> Vec<Match<'_, Self::Payload>>;
+}
+
+impl<P> Completer for YouAutoCompleteMe<'_, P> {
+    type Payload = P;
+
+    /// Completes the input with matching phrases, best match first
+    /// ```
+    /// use you_autocomplete_me::{Completer, TrieNode, YouAutoCompleteMe};
+    /// let mut phrase_book = TrieNode::default();
+    /// phrase_book.insert("hello-world");
+    /// phrase_book.insert("world");
+    /// let completer = YouAutoCompleteMe::new(&phrase_book);
+    ///
+    /// let phrases: Vec<_> = completer.complete("wor").iter().map(|m| m.phrase).collect();
+    /// assert_eq!(phrases, vec!["world", "hello-world"]);
+    /// ```
+    fn complete(&self, input: &str) -> Vec<Match<'_, P>> {
+        self.search(&Search::new(input))
+    }
 }
 
 #[cfg(test)]
@@ -67,4 +369,409 @@ mod tests {
         let builder = YouAutoCompleteMe::builder(&phrase_book);
         builder.with_score_configuration(ScoreConfiguration::default());
     }
+
+    #[test]
+    fn complete_ranks_word_prefix_matches_first() {
+        let mut phrase_book = TrieNode::default();
+        for phrase in ["hello-world", "world", "underworld", "World"] {
+            phrase_book.insert(phrase);
+        }
+        let completer = YouAutoCompleteMe::new(&phrase_book);
+
+        let phrases =
+            |input| -> Vec<&str> { completer.complete(input).iter().map(|m| m.phrase).collect() };
+        assert_eq!(
+            phrases("wor"),
+            vec!["World", "world", "hello-world", "underworld"]
+        );
+        assert_eq!(phrases("Wor"), vec!["World"]);
+    }
+
+    #[test]
+    fn complete_matches_tokens_in_any_order() {
+        let mut phrase_book = TrieNode::default();
+        for phrase in [
+            "I don't think I'll pass the science test",
+            "This is a test",
+            "science fiction",
+        ] {
+            phrase_book.insert(phrase);
+        }
+        let completer = YouAutoCompleteMe::new(&phrase_book);
+
+        let phrases: Vec<_> = completer
+            .complete("test science")
+            .iter()
+            .map(|m| m.phrase)
+            .collect();
+        assert_eq!(phrases, vec!["I don't think I'll pass the science test"]);
+    }
+
+    #[test]
+    fn complete_matches_normalized_phrases() {
+        let mut phrase_book = TrieNode::default()
+            .with_analyzer(normalize::Normalizer::new().with_accent_stripping(true));
+        for phrase in ["Café au lait", "Crème Brûlée", "cafeteria"] {
+            phrase_book.insert(phrase);
+        }
+        let completer = YouAutoCompleteMe::new(&phrase_book);
+
+        let matches = completer.complete("cafe");
+        let phrases: Vec<_> = matches.iter().map(|m| m.phrase).collect();
+        assert_eq!(phrases, vec!["Café au lait", "cafeteria"]);
+        assert_eq!(matches[0].matching_characters(), vec!['C', 'a', 'f', 'é']);
+
+        let matches = completer.complete("brulee");
+        assert_eq!(matches[0].phrase, "Crème Brûlée");
+        assert_eq!(matches[0].positions, vec![6, 7, 8, 9, 10, 11]);
+    }
+
+    #[test]
+    fn complete_matches_transliterated_phrases() {
+        use analyzer::Chain;
+        use transliterate::{Readings, Romaji};
+
+        let mut phrase_book = TrieNode::default()
+            .with_transliterator(
+                Chain::new()
+                    .with(Readings::new().with("東京", "tou kyou"))
+                    .with(Romaji),
+            )
+            .with_transliterator(Readings::new().with("北京", "bei jing"));
+        for phrase in ["東京タワー", "タワーレコード", "北京大学"] {
+            phrase_book.insert(phrase);
+        }
+        let completer = YouAutoCompleteMe::new(&phrase_book);
+
+        let matches = completer.complete("tawa");
+        let phrases: Vec<_> = matches.iter().map(|m| m.phrase).collect();
+        assert_eq!(phrases, vec!["タワーレコード", "東京タワー"]);
+        assert_eq!(matches[1].positions, vec![2, 3]);
+
+        let matches = completer.complete("toukyou");
+        assert_eq!(matches[0].phrase, "東京タワー");
+        assert_eq!(matches[0].positions, vec![0, 1]);
+
+        let matches = completer.complete("beijing");
+        assert_eq!(matches[0].phrase, "北京大学");
+        assert_eq!(matches[0].positions, vec![0, 1]);
+    }
+
+    #[test]
+    fn search_with_phonetic_matches_ranks_them_last() {
+        let mut phrase_book = TrieNode::default().with_phonetic_index(true);
+        for phrase in [
+            "John Smith",
+            "Jon Smyth",
+            "Catherine Smith",
+            "Jonathan Smart",
+        ] {
+            phrase_book.insert(phrase);
+        }
+        let completer = YouAutoCompleteMe::new(&phrase_book);
+
+        let search = Search::builder().search("jon smyth").phonetic(true).build();
+        let phrases: Vec<_> = completer.search(&search).iter().map(|m| m.phrase).collect();
+        assert_eq!(phrases, vec!["Jon Smyth", "John Smith"]);
+
+        let search = Search::builder().search("kathryn").phonetic(true).build();
+        let phrases: Vec<_> = completer.search(&search).iter().map(|m| m.phrase).collect();
+        assert_eq!(phrases, vec!["Catherine Smith"]);
+
+        let phrases: Vec<_> = completer
+            .complete("jon smyth")
+            .iter()
+            .map(|m| m.phrase)
+            .collect();
+        assert_eq!(phrases, vec!["Jon Smyth"]);
+    }
+
+    #[test]
+    fn search_with_phonetic_matches_ranks_them_below_fuzzy_matches() {
+        let mut phrase_book = TrieNode::default().with_phonetic_index(true);
+        for phrase in ["Catherine Smith", "kxaxtxhxrxyxn"] {
+            phrase_book.insert(phrase);
+        }
+        let completer = YouAutoCompleteMe::new(&phrase_book);
+
+        let search = Search::builder()
+            .search("kathryn")
+            .fuzzy(true)
+            .phonetic(true)
+            .build();
+        let matches = completer.search(&search);
+        let phrases: Vec<_> = matches.iter().map(|m| m.phrase).collect();
+        assert_eq!(phrases, vec!["kxaxtxhxrxyxn", "Catherine Smith"]);
+        assert!(matches[0].score < matches[1].score);
+        assert!(!matches[0].phonetic && matches[1].phonetic);
+    }
+
+    #[test]
+    fn complete_analyzes_searches_like_the_phrases() {
+        let mut phrase_book = TrieNode::default();
+        phrase_book.insert("été");
+        let completer = YouAutoCompleteMe::new(&phrase_book);
+
+        assert_eq!(completer.complete("e\u{301}t")[0].phrase, "été");
+        let search = Search::builder().search("e\u{301}t").extended(true).build();
+        assert_eq!(completer.search(&search)[0].phrase, "été");
+
+        let mut phrase_book = TrieNode::default().with_analyzer(
+            analyzer::Chain::new()
+                .with(analyzer::WidthFolding)
+                .with(normalize::Normalizer::new()),
+        );
+        phrase_book.insert("ガイド");
+        let completer = YouAutoCompleteMe::new(&phrase_book);
+        assert_eq!(completer.complete("ｶﾞｲ")[0].phrase, "ガイド");
+    }
+
+    #[test]
+    fn complete_skips_matches_starting_inside_stop_words() {
+        let mut phrase_book = TrieNode::default().with_stop_words(["the".to_string()].into());
+        for phrase in ["The science test", "pass the test", "bathe", "TheEnd"] {
+            phrase_book.insert(phrase);
+        }
+        let completer = YouAutoCompleteMe::new(&phrase_book);
+        let phrases = |search: &str| -> Vec<&str> {
+            let mut phrases: Vec<&str> = completer
+                .complete(search)
+                .into_iter()
+                .map(|m| m.phrase)
+                .collect();
+            phrases.sort_unstable();
+            phrases
+        };
+
+        assert_eq!(phrases("the"), vec!["The science test", "TheEnd", "bathe"]);
+        assert_eq!(phrases("he"), vec!["bathe"]);
+        assert_eq!(phrases("end"), vec!["TheEnd"]);
+    }
+
+    #[test]
+    fn complete_reports_the_alias_of_a_match() {
+        let mut phrase_book = TrieNode::default();
+        for phrase in ["settings", "prefix tree", "user settings"] {
+            phrase_book.insert(phrase);
+        }
+        let aliases = alias::Aliases::new().with("prefs", "settings");
+        let completer = YouAutoCompleteMe::builder(&phrase_book)
+            .with_aliases(&aliases)
+            .build();
+
+        let matches = completer.complete("prefs");
+        let found: Vec<_> = matches.iter().map(|m| (m.phrase, m.alias)).collect();
+        assert_eq!(
+            found,
+            vec![
+                ("settings", Some("prefs")),
+                ("user settings", Some("prefs"))
+            ]
+        );
+        assert_eq!(matches[1].positions, (5..13).collect::<Vec<_>>());
+
+        let matches = completer.complete("pref");
+        let found: Vec<_> = matches.iter().map(|m| (m.phrase, m.alias)).collect();
+        assert_eq!(found, vec![("prefix tree", None)]);
+    }
+
+    #[test]
+    fn complete_expands_many_aliased_tokens_on_their_own() {
+        let mut phrase_book = TrieNode::default();
+        for phrase in ["alpha beta gamma delta", "epsilon"] {
+            phrase_book.insert(phrase);
+        }
+        let mut aliases = alias::Aliases::new();
+        for canonical in ["alpha", "beta", "gamma", "delta"] {
+            aliases = aliases.with("x", canonical);
+        }
+        let completer = YouAutoCompleteMe::builder(&phrase_book)
+            .with_aliases(&aliases)
+            .build();
+
+        let matches = completer.complete(&["x"; 12].join(" "));
+        let found: Vec<_> = matches.iter().map(|m| (m.phrase, m.alias)).collect();
+        assert_eq!(found, vec![("alpha beta gamma delta", Some("x"))]);
+
+        let matches = completer.complete("gamma x");
+        assert_eq!(matches[0].phrase, "alpha beta gamma delta");
+        assert_eq!(matches[0].alias, Some("x"));
+    }
+
+    #[test]
+    fn complete_blends_frecency_into_the_score() {
+        let mut phrase_book = TrieNode::default();
+        phrase_book.insert("test");
+        phrase_book.insert("test-suite");
+        let frecency = frecency::Frecency::new();
+        let completer = YouAutoCompleteMe::builder(&phrase_book)
+            .with_score_configuration(
+                ScoreConfiguration::builder()
+                    .with_frecency_weight(10)
+                    .build(),
+            )
+            .with_frecency(&frecency)
+            .build();
+        let scores = |completer: &YouAutoCompleteMe| -> Vec<(String, i32)> {
+            completer
+                .complete("test")
+                .iter()
+                .map(|m| (m.phrase.to_string(), m.score))
+                .collect()
+        };
+        let before = scores(&completer);
+        assert_eq!(before[0].0, "test");
+
+        completer.record_selection("test", "test-suite");
+        completer.record_selection("test", "test-suite");
+        let after = scores(&completer);
+        assert_eq!(after[0].0, "test-suite");
+        // 10 * ln(1 + 2)
+        assert_eq!(after[0].1, before[1].1 + 11);
+    }
+
+    #[test]
+    fn complete_and_search_agree_on_a_phrase_inserted_again_as_a_record() {
+        let mut phrase_book = TrieNode::default();
+        phrase_book.insert("rust-book");
+        phrase_book.insert_record("rust-book", &[("title", "The Rust Book")]);
+        let completer = YouAutoCompleteMe::new(&phrase_book);
+
+        assert!(phrase_book.search("-book").is_empty());
+        assert!(completer.complete("-book").is_empty());
+        assert_eq!(completer.complete("rust book")[0].phrase, "rust-book");
+    }
+
+    #[test]
+    fn complete_records_by_weighted_fields() {
+        let mut phrase_book = TrieNode::default();
+        phrase_book.insert_record(
+            "rust-book",
+            &[("title", "The Rust Book"), ("description", "learn rust")],
+        );
+        phrase_book.insert_record(
+            "rustlings",
+            &[("title", "Rustlings"), ("description", "small exercises")],
+        );
+        phrase_book.insert_record(
+            "cookbook",
+            &[("title", "Cookbook"), ("description", "recipes in rust")],
+        );
+        let weights = [("title".to_string(), 3)].into();
+        let completer = YouAutoCompleteMe::builder(&phrase_book)
+            .with_score_configuration(
+                ScoreConfiguration::builder()
+                    .with_field_weights(weights)
+                    .build(),
+            )
+            .build();
+
+        let matches = completer.complete("rust");
+        let found: Vec<_> = matches.iter().map(|m| (m.phrase, m.field)).collect();
+        assert_eq!(
+            found,
+            vec![
+                ("rust-book", Some("title")),
+                ("rustlings", Some("title")),
+                ("cookbook", Some("description")),
+            ]
+        );
+        assert_eq!(matches[0].positions, vec![4, 5, 6, 7]);
+        assert_eq!(matches[0].text, "The Rust Book");
+        assert_eq!(matches[0].matching_characters(), vec!['R', 'u', 's', 't']);
+        assert_eq!(matches[2].matching_characters(), vec!['r', 'u', 's', 't']);
+
+        let title = completer.explain("rust", "The Rust Book").unwrap().score;
+        let description = completer.explain("rust", "learn rust").unwrap().score;
+        assert_eq!(matches[0].score, 3 * title + description);
+    }
+
+    #[test]
+    fn complete_with_grouping_collapses_variants() {
+        let mut phrase_book = TrieNode::default();
+        for phrase in ["world", "World", "hello-world"] {
+            phrase_book.insert(phrase);
+        }
+        phrase_book.insert_in_group("NYC", "new-york");
+        phrase_book.insert_in_group("New York City", "new-york");
+
+        let completer = YouAutoCompleteMe::new(&phrase_book);
+        assert_eq!(completer.complete("wor").len(), 3);
+
+        let completer = YouAutoCompleteMe::builder(&phrase_book)
+            .with_grouping(true)
+            .build();
+        let phrases =
+            |input| -> Vec<&str> { completer.complete(input).iter().map(|m| m.phrase).collect() };
+        assert_eq!(phrases("wor"), vec!["World", "hello-world"]);
+        assert_eq!(phrases("Wor"), vec!["World"]);
+        assert_eq!(phrases("nyc"), vec!["New York City"]);
+        assert_eq!(phrases("york"), vec!["New York City"]);
+    }
+
+    #[test]
+    fn search_ranks_like_explaining_every_phrase() {
+        let mut phrase_book = TrieNode::default();
+        let phrases: Vec<String> = (0..2_000)
+            .map(|i| format!("phrase-{i} item {}", i * 7919 % 1_000))
+            .collect();
+        for phrase in &phrases {
+            phrase_book.insert(phrase);
+        }
+        let completer = YouAutoCompleteMe::new(&phrase_book);
+
+        for search in ["1", "item 9", "e-1 7"] {
+            let mut expected: Vec<_> = phrases
+                .iter()
+                .filter_map(|phrase| {
+                    let explanation = completer.explain(search, phrase)?;
+                    Some((phrase.as_str(), explanation.score, explanation.positions))
+                })
+                .collect();
+            expected.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+
+            let found: Vec<_> = completer
+                .search(&Search::new(search))
+                .into_iter()
+                .map(|m| (m.phrase, m.score, m.positions))
+                .collect();
+            assert_eq!(found, expected, "{search}");
+        }
+    }
+
+    #[test]
+    fn complete_uses_the_phrase_book_analyzer() {
+        use analyzer::{Chain, PunctuationStripping, WidthFolding};
+
+        let mut phrase_book = TrieNode::default()
+            .with_analyzer(Chain::new().with(WidthFolding).with(PunctuationStripping));
+        for phrase in ["don't-panic", "ＵＳＢ ｃａｂｌｅ", "dotfiles"] {
+            phrase_book.insert(phrase);
+        }
+        let completer = YouAutoCompleteMe::new(&phrase_book);
+
+        let matches = completer.complete("dontp");
+        assert_eq!(matches[0].phrase, "don't-panic");
+        assert_eq!(matches[0].positions, vec![0, 1, 2, 4, 6]);
+
+        let matches = completer.complete("usb");
+        assert_eq!(matches[0].phrase, "ＵＳＢ ｃａｂｌｅ");
+        assert_eq!(matches[0].positions, vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn search_with_extended_query_syntax() {
+        let mut phrase_book = TrieNode::default();
+        for phrase in ["main.go", "core.go", "core.rb", "core_test.go", "README.md"] {
+            phrase_book.insert(phrase);
+        }
+        let completer = YouAutoCompleteMe::new(&phrase_book);
+
+        let search = Search::builder()
+            .search("^core go$ | rb$ !test")
+            .extended(true)
+            .build();
+        let phrases: Vec<_> = completer.search(&search).iter().map(|m| m.phrase).collect();
+        assert_eq!(phrases, vec!["core.go", "core.rb"]);
+    }
 }
@@ -1,15 +1,90 @@
-struct Match<'a> {
-    search: String,
-    matching_characters: Vec<char>,
-    result: &'a str,
+use std::cmp::Ordering;
+
+/// A phrase matching a search, along with how well it matched
+#[derive(Debug, PartialEq, Eq)]
+pub struct Match<'a, P = ()> {
+    /// The matched phrase as it was inserted into the phrase book
+    pub phrase: &'a str,
+    /// The score given by the [ScoreConfiguration](crate::ScoreConfiguration)
+    pub score: i32,
+    /// The character positions in the [text](Match::text) which matched the search
+    pub positions: Vec<usize>,
+    /// Whether the phrase was matched case sensitively, either through strict or smart casing
+    pub case_sensitive: bool,
+    /// Whether the phrase only matched by sound, ranking it below every spelled match, see
+    /// [Search::phonetic](crate::search::Search::phonetic)
+    pub phonetic: bool,
+    /// The payload attached to the phrase
+    pub payload: Option<&'a P>,
+    /// The alias which expanded the search into the match, see [Aliases](crate::alias::Aliases)
+    pub alias: Option<&'a str>,
+    /// The namespace of the phrase book the phrase is from, see
+    /// [Namespaces](crate::namespace::Namespaces)
+    pub namespace: Option<&'a str>,
+    /// The name of the best matching field of a record, see
+    /// [TrieNode::insert_record](crate::TrieNode::insert_record)
+    pub field: Option<&'a str>,
+    /// The text the positions are in: the phrase, or the best matching field of a record
+    pub text: &'a str,
 }
 
 impl<'a> Match<'a> {
-    fn new(search: String, matching_characters: Vec<char>, result: &'a str) -> Self {
-        Self {
-            search,
-            matching_characters,
-            result,
+    /// The match with the payload attached to its phrase
+    pub(crate) fn with_payload<P>(self, payload: Option<&'a P>) -> Match<'a, P> {
+        Match {
+            phrase: self.phrase,
+            score: self.score,
+            positions: self.positions,
+            case_sensitive: self.case_sensitive,
+            phonetic: self.phonetic,
+            payload,
+            alias: self.alias,
+            namespace: self.namespace,
+            field: self.field,
+            text: self.text,
         }
     }
 }
+
+impl<'a, P> Match<'a, P> {
+    /// Orders matches for ranking: spelled matches first, then from the highest score to the
+    /// lowest, then by phrase
+    pub(crate) fn ranking(&self, other: &Self) -> Ordering {
+        self.phonetic
+            .cmp(&other.phonetic)
+            .then_with(|| other.score.cmp(&self.score))
+            .then_with(|| self.phrase.cmp(other.phrase))
+    }
+
+    /// The matched characters of the [text](Match::text)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use you_autocomplete_me::{Completer, TrieNode, YouAutoCompleteMe};
+    /// let mut phrase_book = TrieNode::default();
+    /// phrase_book.insert("hello-world");
+    /// let completer = YouAutoCompleteMe::new(&phrase_book);
+    ///
+    /// let matches = completer.complete("wor");
+    /// assert_eq!(matches[0].matching_characters(), vec!['w', 'o', 'r']);
+    /// ```
+    pub fn matching_characters(&self) -> Vec<char> {
+        let mut positions = self.positions.iter().peekable();
+        self.text
+            .chars()
+            .enumerate()
+            .filter_map(|(i, c)| positions.next_if_eq(&&i).map(|_| c))
+            .collect()
+    }
+}
+
+/// The matches of a search which may have been interrupted, see [cancel](crate::cancel)
+#[derive(Debug, PartialEq, Eq)]
+pub struct SearchResults<'a, P = ()> {
+    /// The matches found, ordered from the highest score to the lowest
+    pub matches: Vec<Match<'a, P>>,
+    /// Whether the search was cancelled or ran past its deadline before every candidate was
+    /// found and scored
+    pub incomplete: bool,
+}
@@ -0,0 +1,227 @@
+//! # Namespace Module
+//!
+//! Completes from several phrase books at once, each registered under a namespace such as
+//! `commands`, `files` or `users`. Searches may begin with contexts like `@users`, which
+//! restrict the search to the namespaces starting with the context. The matches of every
+//! searched namespace are ranked together, after adding the boost of their namespace, and
+//! report it in [Match::namespace](crate::Match::namespace).
+//!
+//! ## Example
+//!
+//! ```
+//! use you_autocomplete_me::namespace::Namespaces;
+//! use you_autocomplete_me::{Completer, TrieNode, YouAutoCompleteMe};
+//! let mut commands = TrieNode::default();
+//! commands.insert("join channel");
+//! let mut users = TrieNode::default();
+//! users.insert("john");
+//! let namespaces = Namespaces::new()
+//!     .with("commands", YouAutoCompleteMe::new(&commands))
+//!     .with("users", YouAutoCompleteMe::new(&users))
+//!     .with_boost("commands", 10);
+//!
+//! let matches = namespaces.complete("jo");
+//! assert_eq!(matches[0].phrase, "join channel");
+//! assert_eq!(matches[0].namespace, Some("commands"));
+//!
+//! let matches = namespaces.complete("@users jo");
+//! assert_eq!(matches.len(), 1);
+//! assert_eq!(matches[0].phrase, "john");
+//! assert_eq!(matches[0].namespace, Some("users"));
+//! ```
+
+use crate::search::Search;
+use crate::{Completer, Match, SearchResults, YouAutoCompleteMe};
+
+/// Auto completers registered under namespaces, along with the boost of each namespace
+#[derive(Debug)]
+pub struct Namespaces<'a, P = ()> {
+    namespaces: Vec<Namespace<'a, P>>,
+}
+
+#[derive(Debug)]
+struct Namespace<'a, P> {
+    name: &'a str,
+    completer: YouAutoCompleteMe<'a, P>,
+    boost: i32,
+}
+
+impl<P> Default for Namespaces<'_, P> {
+    fn default() -> Self {
+        Self {
+            namespaces: Vec::new(),
+        }
+    }
+}
+
+impl<'a, P> Namespaces<'a, P> {
+    /// Creates an empty set of namespaces
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Registers the auto completer under the namespace, replacing any completer already
+    /// registered under it
+    pub fn with(mut self, name: &'a str, completer: YouAutoCompleteMe<'a, P>) -> Self {
+        match self.namespaces.iter_mut().find(|n| n.name == name) {
+            Some(namespace) => namespace.completer = completer,
+            None => self.namespaces.push(Namespace {
+                name,
+                completer,
+                boost: 0,
+            }),
+        }
+        self
+    }
+
+    /// Adds the boost to the score of every match from the namespace. Negative boosts rank
+    /// the namespace lower.
+    pub fn with_boost(mut self, name: &str, boost: i32) -> Self {
+        if let Some(namespace) = self.namespaces.iter_mut().find(|n| n.name == name) {
+            namespace.boost = boost;
+        }
+        self
+    }
+
+    /// The auto completer registered under the namespace
+    pub fn get(&self, name: &str) -> Option<&YouAutoCompleteMe<'a, P>> {
+        self.namespaces
+            .iter()
+            .find(|n| n.name == name)
+            .map(|n| &n.completer)
+    }
+
+    /// Phrases of the namespaces matching the search, ordered from the highest boosted score
+    /// to the lowest. Contexts at the start of the search pick the namespaces searched.
+    pub fn search(&self, search: &Search<'_>) -> Vec<Match<'a, P>> {
+        self.search_results(search).matches
+    }
+
+    /// Phrases of the namespaces matching the search like [search](Namespaces::search),
+    /// incomplete when the search of any namespace was cancelled or ran past its deadline, see
+    /// [cancel](crate::cancel)
+    pub fn search_results(&self, search: &Search<'_>) -> SearchResults<'a, P> {
+        let (contexts, rest) = contexts(search.search);
+        let search = Search {
+            search: rest,
+            ..search.clone()
+        };
+
+        let mut matches: Vec<Match<'a, P>> = Vec::new();
+        let mut incomplete = false;
+        for namespace in self.namespaces.iter().filter(|namespace| {
+            contexts.is_empty()
+                || contexts
+                    .iter()
+                    .any(|context| namespace.name.starts_with(context))
+        }) {
+            let results = namespace.completer.search_results(&search);
+            incomplete |= results.incomplete;
+            matches.extend(results.matches.into_iter().map(|found| Match {
+                score: found.score + namespace.boost,
+                namespace: Some(namespace.name),
+                ..found
+            }));
+        }
+        // stable, so equal matches keep the order their namespaces were registered in
+        matches.sort_by(Match::ranking);
+        SearchResults {
+            matches,
+            incomplete,
+        }
+    }
+}
+
+impl<P> Completer for Namespaces<'_, P> {
+    type Payload = P;
+
+    /// Completes the input from the namespaces, best match first
+    fn complete(&self, input: &str) -> Vec<Match<'_, P>> {
+        self.search(&Search::new(input))
+    }
+}
+
+/// The `@` prefixed contexts at the start of the search, and the rest of the search
+fn contexts(search: &str) -> (Vec<&str>, &str) {
+    let mut contexts = Vec::new();
+    let mut rest = search.trim_start();
+    while let Some(context) = rest.strip_prefix('@') {
+        let end = context.find(char::is_whitespace).unwrap_or(context.len());
+        if end > 0 {
+            contexts.push(&context[..end]);
+        }
+        rest = context[end..].trim_start();
+    }
+    (contexts, rest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cancel::CancellationToken;
+    use crate::TrieNode;
+
+    #[test]
+    fn contexts_are_split_from_the_search() {
+        assert_eq!(contexts("@users jo"), (vec!["users"], "jo"));
+        assert_eq!(
+            contexts(" @users @files  read me"),
+            (vec!["users", "files"], "read me")
+        );
+        assert_eq!(contexts("@ jo"), (vec![], "jo"));
+        assert_eq!(contexts("jo @users"), (vec![], "jo @users"));
+    }
+
+    #[test]
+    fn search_merges_namespaces_with_boosts() {
+        let mut files = TrieNode::default();
+        files.insert("readme.md");
+        let mut users = TrieNode::default();
+        users.insert("reader");
+        let namespaces = Namespaces::new()
+            .with("files", YouAutoCompleteMe::new(&files))
+            .with("users", YouAutoCompleteMe::new(&users));
+
+        fn found<'a>(namespaces: &Namespaces<'a>, input: &str) -> Vec<(&'a str, Option<&'a str>)> {
+            namespaces
+                .search(&Search::new(input))
+                .iter()
+                .map(|m| (m.phrase, m.namespace))
+                .collect()
+        }
+        let unboosted = found(&namespaces, "read");
+        assert_eq!(unboosted.len(), 2);
+
+        let namespaces = namespaces.with_boost("files", -50);
+        assert_eq!(
+            found(&namespaces, "read"),
+            vec![("reader", Some("users")), ("readme.md", Some("files"))]
+        );
+        assert_eq!(
+            found(&namespaces, "@fi read"),
+            vec![("readme.md", Some("files"))]
+        );
+        assert!(found(&namespaces, "@commands read").is_empty());
+    }
+
+    #[test]
+    fn search_results_of_cancelled_searches_are_incomplete() {
+        let mut files = TrieNode::default();
+        files.insert("readme.md");
+        let namespaces = Namespaces::new().with("files", YouAutoCompleteMe::new(&files));
+        let token = CancellationToken::new();
+        let search = Search::builder()
+            .search("read")
+            .cancellation(token.clone())
+            .build();
+
+        let results = namespaces.search_results(&search);
+        assert_eq!(results.matches.len(), 1);
+        assert!(!results.incomplete);
+
+        token.cancel();
+        let results = namespaces.search_results(&search);
+        assert!(results.matches.is_empty());
+        assert!(results.incomplete);
+    }
+}
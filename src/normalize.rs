@@ -0,0 +1,171 @@
+//! # Normalize Module
+//!
+//! The default [Analyzer] of the phrase book. Normalizes phrases when they are inserted into the
+//! phrase book and searches before they are matched, so that "Café", "CAFÉ" and "cafe" can find
+//! each other. Matches always refer to the original text of the phrase.
+//!
+//! ## Pipeline
+//!
+//! ```text
+//! text --accent stripping--> --normalization form--> --case folding--> normalized text
+//! ```
+//!
+//! Accent stripping is optional. Case folding is applied by the phrase book after any analyzer,
+//! and only when matching case insensitively.
+//!
+//! ## Example
+//!
+//! ```
+//! use you_autocomplete_me::normalize::{NormalizationForm, Normalizer};
+//! let normalizer = Normalizer::new()
+//!     .with_form(NormalizationForm::Nfkd)
+//!     .with_accent_stripping(true);
+//!
+//! assert_eq!(normalizer.normalize("Café"), "cafe");
+//! assert_eq!(normalizer.normalize("Straße"), "strasse");
+//! ```
+
+use unicode_normalization::char::{canonical_combining_class, is_combining_mark};
+use unicode_normalization::UnicodeNormalization;
+
+use crate::analyzer::{self, AnalyzedText, Analyzer};
+
+/// The Unicode normalization form applied to text
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum NormalizationForm {
+    /// Leave the text as is
+    None,
+    /// Canonical composition
+    #[default]
+    Nfc,
+    /// Canonical decomposition
+    Nfd,
+    /// Compatibility composition
+    Nfkc,
+    /// Compatibility decomposition
+    Nfkd,
+}
+
+/// Normalizes phrases when they are indexed and searches before they are matched
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Normalizer {
+    form: NormalizationForm,
+    accent_stripping: bool,
+}
+
+impl Normalizer {
+    /// Creates a normalizer using NFC without accent stripping
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Sets the Unicode normalization form
+    pub fn with_form(mut self, form: NormalizationForm) -> Self {
+        self.form = form;
+        self
+    }
+
+    /// Removes combining marks, so that "é" is matched by "e"
+    pub fn with_accent_stripping(mut self, accent_stripping: bool) -> Self {
+        self.accent_stripping = accent_stripping;
+        self
+    }
+
+    /// Normalizes and case folds the text
+    pub fn normalize(&self, text: &str) -> String {
+        analyzer::analyze(self, text, true).key()
+    }
+
+    fn normalize_segment(&self, segment: &[char]) -> Vec<char> {
+        let segment: String = match self.accent_stripping {
+            true => segment
+                .iter()
+                .copied()
+                .nfd()
+                .filter(|c| !is_combining_mark(*c))
+                .collect(),
+            false => segment.iter().collect(),
+        };
+        match self.form {
+            NormalizationForm::None => segment.chars().collect(),
+            NormalizationForm::Nfc => segment.nfc().collect(),
+            NormalizationForm::Nfd => segment.nfd().collect(),
+            NormalizationForm::Nfkc => segment.nfkc().collect(),
+            NormalizationForm::Nfkd => segment.nfkd().collect(),
+        }
+    }
+}
+
+impl Analyzer for Normalizer {
+    fn analyze(&self, text: AnalyzedText) -> AnalyzedText {
+        let mut normalized = AnalyzedText::default();
+
+        // normalize each starter along with the combining characters following it,
+        // so every normalized character maps back to the starter it came from
+        let mut start = 0;
+        while start < text.chars.len() {
+            let mut end = start + 1;
+            while end < text.chars.len() && canonical_combining_class(text.chars[end]) != 0 {
+                end += 1;
+            }
+            for c in self.normalize_segment(&text.chars[start..end]) {
+                normalized.push(c, text.origins[start]);
+            }
+            start = end;
+        }
+
+        normalized
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn case_folding_handles_non_ascii() {
+        let normalizer = Normalizer::new();
+
+        assert_eq!(normalizer.normalize("ÉCOLE"), "école");
+        assert_eq!(normalizer.normalize("Straße"), "strasse");
+        assert_eq!(
+            normalizer.normalize("ΣΊΣΥΦΟΣ"),
+            normalizer.normalize("σίσυφος")
+        );
+        assert_eq!(normalizer.normalize("ﬁle"), normalizer.normalize("FILE"));
+    }
+
+    #[test]
+    fn composed_and_decomposed_text_are_equal() {
+        let normalizer = Normalizer::new();
+
+        assert_eq!(
+            normalizer.normalize("Cafe\u{301}"),
+            normalizer.normalize("Café")
+        );
+    }
+
+    #[test]
+    fn accent_stripping_removes_combining_marks() {
+        let normalizer = Normalizer::new().with_accent_stripping(true);
+
+        assert_eq!(normalizer.normalize("Crème Brûlée"), "creme brulee");
+        assert_eq!(normalizer.normalize("Cafe\u{301}"), "cafe");
+    }
+
+    #[test]
+    fn compatibility_forms_fold_ligatures() {
+        let normalizer = Normalizer::new().with_form(NormalizationForm::Nfkd);
+
+        assert_eq!(normalizer.normalize("ﬁle"), "file");
+    }
+
+    #[test]
+    fn normalized_characters_map_to_their_origin() {
+        let normalizer = Normalizer::new().with_accent_stripping(true);
+        let text = analyzer::analyze(&normalizer, "Cafe\u{301} ß", true);
+
+        assert_eq!(text.key(), "cafe ss");
+        assert_eq!(text.origins(), &[0, 1, 2, 3, 5, 6, 6]);
+    }
+}
@@ -0,0 +1,153 @@
+//! # Page Module
+//!
+//! Pages through completions for interfaces loading more results as the user scrolls. The
+//! first page searches and scores every candidate once, as the best matches are only known
+//! once all of them are scored, but it only sorts its own matches: those before the page are
+//! selected and dropped without sorting, and those after it are kept unsorted by its [Cursor].
+//! Every later page selects its matches from the ones remaining in time linear in their number,
+//! without searching or scoring again, then sorts them. A cursor borrows the phrase book,
+//! which therefore cannot change while it is in use.
+//!
+//! ## Example
+//!
+//! ```
+//! use you_autocomplete_me::search::Search;
+//! use you_autocomplete_me::{TrieNode, YouAutoCompleteMe};
+//! let mut phrase_book = TrieNode::default();
+//! for phrase in ["test", "testing", "tested", "contest"] {
+//!     phrase_book.insert(phrase);
+//! }
+//! let completer = YouAutoCompleteMe::new(&phrase_book);
+//!
+//! let page = completer.search_page(&Search::new("test"), 0, 2);
+//! assert_eq!(page.matches.len(), 2);
+//! assert_eq!(page.remaining(), 2);
+//!
+//! let page = page.cursor.unwrap().next_page(2);
+//! assert_eq!(page.matches[1].phrase, "contest");
+//! assert!(page.cursor.is_none());
+//! ```
+
+use crate::{Match, SearchResults};
+
+/// A page of matches, along with a cursor to the following matches if there are any
+#[derive(Debug)]
+pub struct Page<'a, P = ()> {
+    /// The matches of the page, best match first
+    pub matches: Vec<Match<'a, P>>,
+    /// Where the next page begins, `None` on the last page
+    pub cursor: Option<Cursor<'a, P>>,
+    /// Whether the search was interrupted before every candidate was found and scored, so the
+    /// pages may be missing matches, see [SearchResults](crate::SearchResults::incomplete)
+    pub incomplete: bool,
+}
+
+impl<'a, P> Page<'a, P> {
+    /// Pages the matches of the results in ranking order, skipping `offset` of them and taking
+    /// up to `limit`
+    pub(crate) fn new(results: SearchResults<'a, P>, offset: usize, limit: usize) -> Self {
+        let mut cursor = Cursor {
+            remaining: results.matches,
+            incomplete: results.incomplete,
+        };
+        cursor.select(offset);
+        cursor.next_page(limit)
+    }
+
+    /// The number of matches after this page
+    pub fn remaining(&self) -> usize {
+        self.cursor
+            .as_ref()
+            .map_or(0, |cursor| cursor.remaining.len())
+    }
+}
+
+/// An opaque position within ranked matches, resuming after the matches already paged
+#[derive(Debug)]
+pub struct Cursor<'a, P = ()> {
+    /// The matches after the pages so far, unsorted
+    remaining: Vec<Match<'a, P>>,
+    incomplete: bool,
+}
+
+impl<'a, P> Cursor<'a, P> {
+    /// The next page of up to `limit` matches
+    pub fn next_page(mut self, limit: usize) -> Page<'a, P> {
+        let mut matches = self.select(limit);
+        matches.sort_by(Match::ranking);
+        let incomplete = self.incomplete;
+        let cursor = (!self.remaining.is_empty()).then_some(self);
+        Page {
+            matches,
+            cursor,
+            incomplete,
+        }
+    }
+
+    /// Removes the best `count` of the remaining matches, unsorted
+    fn select(&mut self, count: usize) -> Vec<Match<'a, P>> {
+        if count < self.remaining.len() {
+            self.remaining.select_nth_unstable_by(count, Match::ranking);
+        }
+        let count = count.min(self.remaining.len());
+        self.remaining.drain(..count).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cancel::CancellationToken;
+    use crate::search::Search;
+    use crate::{TrieNode, YouAutoCompleteMe};
+
+    #[test]
+    fn pages_cover_every_match_in_order() {
+        let mut phrase_book = TrieNode::default();
+        for phrase in [
+            "a1", "a2", "a3", "banana", "xa", "b-a", "data", "a5", "alpha",
+        ] {
+            phrase_book.insert(phrase);
+        }
+        let completer = YouAutoCompleteMe::new(&phrase_book);
+        let search = Search::new("a");
+        let all: Vec<_> = completer.search(&search).iter().map(|m| m.phrase).collect();
+
+        let mut paged = Vec::new();
+        let mut page = completer.search_page(&search, 1, 2);
+        loop {
+            paged.extend(page.matches.iter().map(|m| m.phrase));
+            match page.cursor {
+                Some(cursor) => page = cursor.next_page(2),
+                None => break,
+            }
+        }
+        assert_eq!(paged, all[1..]);
+
+        let page = completer.search_page(&search, 10, 2);
+        assert!(page.matches.is_empty());
+        assert!(page.cursor.is_none());
+
+        let page = completer.search_page(&search, 0, 10);
+        assert_eq!(
+            page.matches.iter().map(|m| m.phrase).collect::<Vec<_>>(),
+            all
+        );
+        assert!(page.cursor.is_none());
+        assert!(!page.incomplete);
+    }
+
+    #[test]
+    fn pages_of_cancelled_searches_are_incomplete() {
+        let mut phrase_book = TrieNode::default();
+        phrase_book.insert("a1");
+        let completer = YouAutoCompleteMe::new(&phrase_book);
+        let token = CancellationToken::new();
+        token.cancel();
+        let search = Search::builder().search("a").cancellation(token).build();
+
+        let page = completer.search_page(&search, 0, 2);
+        assert!(page.matches.is_empty());
+        assert!(page.cursor.is_none());
+        assert!(page.incomplete);
+    }
+}
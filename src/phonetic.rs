@@ -0,0 +1,670 @@
+//! # Phonetic Module
+//!
+//! Double Metaphone codes for the words of phrases, so that searches can find phrases which
+//! sound alike but are spelled differently. The phrase book keeps an index of these codes when
+//! [with_phonetic_index](crate::TrieNode::with_phonetic_index) is set, and searches use it when
+//! [Search::phonetic](crate::search::Search::phonetic) is set.
+//!
+//! ## Example
+//!
+//! ```
+//! use you_autocomplete_me::phonetic::double_metaphone;
+//! assert_eq!(double_metaphone("Smith"), double_metaphone("Smyth"));
+//! assert_eq!(double_metaphone("John").0, double_metaphone("Jon").0);
+//! ```
+
+use std::ops::Range;
+
+/// The longest code produced for a word
+const CODE_LENGTH: usize = 4;
+
+/// The primary and alternate Double Metaphone codes of a word.
+/// The alternate code equals the primary one when the word has a single pronunciation.
+///
+/// # Examples
+///
+/// ```
+/// use you_autocomplete_me::phonetic::double_metaphone;
+/// assert_eq!(double_metaphone("Schmidt"), ("XMT".to_string(), "SMT".to_string()));
+/// assert_eq!(double_metaphone("Catherine"), double_metaphone("Kathryn"));
+/// ```
+pub fn double_metaphone(word: &str) -> (String, String) {
+    DoubleMetaphone::new(word).encode()
+}
+
+/// The character ranges of the words in the text, runs of alphabetic characters
+pub(crate) fn words(text: &str) -> Vec<Range<usize>> {
+    let mut words = Vec::new();
+    let mut start = None;
+    for (i, c) in text.chars().chain(std::iter::once(' ')).enumerate() {
+        match (c.is_alphabetic(), start) {
+            (true, None) => start = Some(i),
+            (false, Some(from)) => {
+                words.push(from..i);
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    words
+}
+
+/// The distinct codes of a word, empty if it has no sounds
+pub(crate) fn codes(word: &str) -> Vec<String> {
+    let (primary, alternate) = double_metaphone(word);
+    let mut codes = vec![primary, alternate];
+    codes.dedup();
+    codes.retain(|code| !code.is_empty());
+    codes
+}
+
+struct DoubleMetaphone {
+    chars: Vec<char>,
+    primary: String,
+    alternate: String,
+    slavo_germanic: bool,
+}
+
+impl DoubleMetaphone {
+    fn new(word: &str) -> Self {
+        let chars: Vec<char> = word.to_uppercase().chars().collect();
+        let text: String = chars.iter().collect();
+        let slavo_germanic = text.contains('W')
+            || text.contains('K')
+            || text.contains("CZ")
+            || text.contains("WITZ");
+        Self {
+            chars,
+            primary: String::new(),
+            alternate: String::new(),
+            slavo_germanic,
+        }
+    }
+
+    fn at(&self, i: isize) -> char {
+        usize::try_from(i)
+            .ok()
+            .and_then(|i| self.chars.get(i))
+            .copied()
+            .unwrap_or('\0')
+    }
+
+    /// Whether any of the options appear at `start`
+    fn is_at(&self, start: isize, options: &[&str]) -> bool {
+        let Ok(start) = usize::try_from(start) else {
+            return false;
+        };
+        options.iter().any(|option| {
+            let option: Vec<char> = option.chars().collect();
+            self.chars.get(start..start + option.len()) == Some(&option[..])
+        })
+    }
+
+    fn is_vowel(&self, i: isize) -> bool {
+        matches!(self.at(i), 'A' | 'E' | 'I' | 'O' | 'U' | 'Y')
+    }
+
+    fn add(&mut self, code: &str) {
+        self.add_both(code, code);
+    }
+
+    fn add_both(&mut self, primary: &str, alternate: &str) {
+        self.primary.push_str(primary);
+        self.alternate.push_str(alternate);
+    }
+
+    fn encode(mut self) -> (String, String) {
+        let length = self.chars.len() as isize;
+        let last = length - 1;
+        let slavo = self.slavo_germanic;
+        let mut c: isize = 0;
+
+        if self.is_at(0, &["GN", "KN", "PN", "WR", "PS"]) {
+            c += 1;
+        }
+        if self.at(0) == 'X' {
+            self.add("S");
+            c += 1;
+        }
+
+        while c < length && (self.primary.len() < CODE_LENGTH || self.alternate.len() < CODE_LENGTH)
+        {
+            let next = self.at(c + 1);
+            c += match self.at(c) {
+                'A' | 'E' | 'I' | 'O' | 'U' | 'Y' => {
+                    if c == 0 {
+                        self.add("A");
+                    }
+                    1
+                }
+                'B' => {
+                    self.add("P");
+                    if next == 'B' {
+                        2
+                    } else {
+                        1
+                    }
+                }
+                'Ç' => {
+                    self.add("S");
+                    1
+                }
+                'C' => self.encode_c(c),
+                'D' => {
+                    if self.is_at(c, &["DG"]) {
+                        if self.is_at(c + 2, &["I", "E", "Y"]) {
+                            self.add("J");
+                            3
+                        } else {
+                            self.add("TK");
+                            2
+                        }
+                    } else {
+                        self.add("T");
+                        if self.is_at(c, &["DT", "DD"]) {
+                            2
+                        } else {
+                            1
+                        }
+                    }
+                }
+                'F' => {
+                    self.add("F");
+                    if next == 'F' {
+                        2
+                    } else {
+                        1
+                    }
+                }
+                'G' => self.encode_g(c),
+                // H is only pronounced between vowels or at the start before a vowel
+                'H' if (c == 0 || self.is_vowel(c - 1)) && self.is_vowel(c + 1) => {
+                    self.add("H");
+                    2
+                }
+                'J' => {
+                    if self.is_at(c, &["JOSE"]) || self.is_at(0, &["SAN "]) {
+                        if (c == 0 && self.at(c + 4) == ' ') || self.is_at(0, &["SAN "]) {
+                            self.add("H");
+                        } else {
+                            self.add_both("J", "H");
+                        }
+                        1
+                    } else {
+                        if c == 0 {
+                            self.add_both("J", "A");
+                        } else if self.is_vowel(c - 1) && !slavo && matches!(next, 'A' | 'O') {
+                            self.add_both("J", "H");
+                        } else if c == last {
+                            self.add_both("J", "");
+                        } else if !self.is_at(c + 1, &["L", "T", "K", "S", "N", "M", "B", "Z"])
+                            && !self.is_at(c - 1, &["S", "K", "L"])
+                        {
+                            self.add("J");
+                        }
+                        if next == 'J' {
+                            2
+                        } else {
+                            1
+                        }
+                    }
+                }
+                'K' => {
+                    self.add("K");
+                    if next == 'K' {
+                        2
+                    } else {
+                        1
+                    }
+                }
+                'L' => {
+                    if next == 'L' {
+                        let spanish = (c == length - 3
+                            && self.is_at(c - 1, &["ILLO", "ILLA", "ALLE"]))
+                            || ((self.is_at(last - 1, &["AS", "OS"])
+                                || self.is_at(last, &["A", "O"]))
+                                && self.is_at(c - 1, &["ALLE"]));
+                        if spanish {
+                            self.add_both("L", "");
+                        } else {
+                            self.add("L");
+                        }
+                        2
+                    } else {
+                        self.add("L");
+                        1
+                    }
+                }
+                'M' => {
+                    self.add("M");
+                    if (self.is_at(c - 1, &["UMB"])
+                        && (c + 1 == last || self.is_at(c + 2, &["ER"])))
+                        || next == 'M'
+                    {
+                        2
+                    } else {
+                        1
+                    }
+                }
+                'N' => {
+                    self.add("N");
+                    if next == 'N' {
+                        2
+                    } else {
+                        1
+                    }
+                }
+                'Ñ' => {
+                    self.add("N");
+                    1
+                }
+                'P' => {
+                    if next == 'H' {
+                        self.add("F");
+                        2
+                    } else {
+                        self.add("P");
+                        if matches!(next, 'P' | 'B') {
+                            2
+                        } else {
+                            1
+                        }
+                    }
+                }
+                'Q' => {
+                    self.add("K");
+                    if next == 'Q' {
+                        2
+                    } else {
+                        1
+                    }
+                }
+                'R' => {
+                    if c == last
+                        && !slavo
+                        && self.is_at(c - 2, &["IE"])
+                        && !self.is_at(c - 4, &["ME", "MA"])
+                    {
+                        self.add_both("", "R");
+                    } else {
+                        self.add("R");
+                    }
+                    if next == 'R' {
+                        2
+                    } else {
+                        1
+                    }
+                }
+                'S' => self.encode_s(c),
+                'T' => {
+                    if self.is_at(c, &["TION", "TIA", "TCH"]) {
+                        self.add("X");
+                        3
+                    } else if self.is_at(c, &["TH", "TTH"]) {
+                        if self.is_at(c + 2, &["OM", "AM"])
+                            || self.is_at(0, &["VAN ", "VON ", "SCH"])
+                        {
+                            self.add("T");
+                        } else {
+                            self.add_both("0", "T");
+                        }
+                        2
+                    } else {
+                        self.add("T");
+                        if matches!(next, 'T' | 'D') {
+                            2
+                        } else {
+                            1
+                        }
+                    }
+                }
+                'V' => {
+                    self.add("F");
+                    if next == 'V' {
+                        2
+                    } else {
+                        1
+                    }
+                }
+                'W' => {
+                    if self.is_at(c, &["WR"]) {
+                        self.add("R");
+                        2
+                    } else {
+                        if c == 0 && (self.is_vowel(c + 1) || self.is_at(c, &["WH"])) {
+                            if self.is_vowel(c + 1) {
+                                self.add_both("A", "F");
+                            } else {
+                                self.add("A");
+                            }
+                        }
+                        if (c == last && self.is_vowel(c - 1))
+                            || self.is_at(c - 1, &["EWSKI", "EWSKY", "OWSKI", "OWSKY"])
+                            || self.is_at(0, &["SCH"])
+                        {
+                            self.add_both("", "F");
+                            1
+                        } else if self.is_at(c, &["WICZ", "WITZ"]) {
+                            self.add_both("TS", "FX");
+                            4
+                        } else {
+                            1
+                        }
+                    }
+                }
+                'X' => {
+                    let silent = c == last
+                        && (self.is_at(c - 3, &["IAU", "EAU"]) || self.is_at(c - 2, &["AU", "OU"]));
+                    if !silent {
+                        self.add("KS");
+                    }
+                    if matches!(next, 'C' | 'X') {
+                        2
+                    } else {
+                        1
+                    }
+                }
+                'Z' => {
+                    if next == 'H' {
+                        self.add("J");
+                        2
+                    } else {
+                        if self.is_at(c + 1, &["ZO", "ZI", "ZA"])
+                            || (slavo && c > 0 && self.at(c - 1) != 'T')
+                        {
+                            self.add_both("S", "TS");
+                        } else {
+                            self.add("S");
+                        }
+                        if next == 'Z' {
+                            2
+                        } else {
+                            1
+                        }
+                    }
+                }
+                _ => 1,
+            };
+        }
+
+        self.primary.truncate(CODE_LENGTH);
+        self.alternate.truncate(CODE_LENGTH);
+        (self.primary, self.alternate)
+    }
+
+    fn encode_c(&mut self, c: isize) -> isize {
+        // germanic "ACH", as in "Bacher"
+        if c > 1
+            && !self.is_vowel(c - 2)
+            && self.is_at(c - 1, &["ACH"])
+            && self.at(c + 2) != 'I'
+            && (self.at(c + 2) != 'E' || self.is_at(c - 2, &["BACHER", "MACHER"]))
+        {
+            self.add("K");
+            return 2;
+        }
+        if c == 0 && self.is_at(c, &["CAESAR"]) {
+            self.add("S");
+            return 2;
+        }
+        if self.is_at(c, &["CHIA"]) {
+            self.add("K");
+            return 2;
+        }
+        if self.is_at(c, &["CH"]) {
+            // greek roots, as in "chorus" and "character"
+            let greek = c == 0
+                && (self.is_at(c + 1, &["HARAC", "HARIS"])
+                    || self.is_at(c + 1, &["HOR", "HYM", "HIA", "HEM"]))
+                && !self.is_at(0, &["CHORE"]);
+            // germanic roots, as in "orchestra" and "Schumacher"
+            let germanic = self.is_at(0, &["VAN ", "VON ", "SCH"])
+                || self.is_at(c - 2, &["ORCHES", "ARCHIT", "ORCHID"])
+                || self.is_at(c + 2, &["T", "S"])
+                || ((c == 0 || self.is_at(c - 1, &["A", "O", "U", "E"]))
+                    && self.is_at(c + 2, &["L", "R", "N", "M", "B", "H", "F", "V", "W", " "]));
+
+            if c > 0 && self.is_at(c, &["CHAE"]) {
+                self.add_both("K", "X");
+            } else if greek || germanic {
+                self.add("K");
+            } else if c > 0 {
+                if self.is_at(0, &["MC"]) {
+                    self.add("K");
+                } else {
+                    self.add_both("X", "K");
+                }
+            } else {
+                self.add("X");
+            }
+            return 2;
+        }
+        if self.is_at(c, &["CZ"]) && !self.is_at(c - 2, &["WICZ"]) {
+            self.add_both("S", "X");
+            return 2;
+        }
+        if self.is_at(c + 1, &["CIA"]) {
+            self.add("X");
+            return 3;
+        }
+        if self.is_at(c, &["CC"]) && !(c == 1 && self.at(0) == 'M') {
+            if self.is_at(c + 2, &["I", "E", "H"]) && !self.is_at(c + 2, &["HU"]) {
+                if (c == 1 && self.at(c - 1) == 'A') || self.is_at(c - 1, &["UCCEE", "UCCES"]) {
+                    self.add("KS");
+                } else {
+                    self.add("X");
+                }
+                return 3;
+            }
+            self.add("K");
+            return 2;
+        }
+        if self.is_at(c, &["CK", "CG", "CQ"]) {
+            self.add("K");
+            return 2;
+        }
+        if self.is_at(c, &["CI", "CE", "CY"]) {
+            if self.is_at(c, &["CIO", "CIE", "CIA"]) {
+                self.add_both("S", "X");
+            } else {
+                self.add("S");
+            }
+            return 2;
+        }
+
+        self.add("K");
+        if self.is_at(c + 1, &[" C", " Q", " G"]) {
+            3
+        } else if self.is_at(c + 1, &["C", "K", "Q"]) && !self.is_at(c + 1, &["CE", "CI"]) {
+            2
+        } else {
+            1
+        }
+    }
+
+    fn encode_g(&mut self, c: isize) -> isize {
+        let slavo = self.slavo_germanic;
+        let next = self.at(c + 1);
+
+        if next == 'H' {
+            if c > 0 && !self.is_vowel(c - 1) {
+                self.add("K");
+            } else if c == 0 {
+                if self.at(c + 2) == 'I' {
+                    self.add("J");
+                } else {
+                    self.add("K");
+                }
+            } else if (c > 1 && self.is_at(c - 2, &["B", "H", "D"]))
+                || (c > 2 && self.is_at(c - 3, &["B", "H", "D"]))
+                || (c > 3 && self.is_at(c - 4, &["B", "H"]))
+            {
+                // silent, as in "Hugh" and "bought"
+            } else if c > 2
+                && self.at(c - 1) == 'U'
+                && self.is_at(c - 3, &["C", "G", "L", "R", "T"])
+            {
+                self.add("F");
+            } else if self.at(c - 1) != 'I' {
+                self.add("K");
+            }
+            return 2;
+        }
+        if next == 'N' {
+            if c == 1 && self.is_vowel(0) && !slavo {
+                self.add_both("KN", "N");
+            } else if !self.is_at(c + 2, &["EY"]) && next != 'Y' && !slavo {
+                self.add_both("N", "KN");
+            } else {
+                self.add("KN");
+            }
+            return 2;
+        }
+        if self.is_at(c + 1, &["LI"]) && !slavo {
+            self.add_both("KL", "L");
+            return 2;
+        }
+        if c == 0
+            && (next == 'Y'
+                || self.is_at(
+                    c + 1,
+                    &[
+                        "ES", "EP", "EB", "EL", "EY", "IB", "IL", "IN", "IE", "EI", "ER",
+                    ],
+                ))
+        {
+            self.add_both("K", "J");
+            return 2;
+        }
+        if (self.is_at(c + 1, &["ER"]) || next == 'Y')
+            && !self.is_at(0, &["DANGER", "RANGER", "MANGER"])
+            && !self.is_at(c - 1, &["E", "I"])
+            && !self.is_at(c - 1, &["RGY", "OGY"])
+        {
+            self.add_both("K", "J");
+            return 2;
+        }
+        if self.is_at(c + 1, &["E", "I", "Y"]) || self.is_at(c - 1, &["AGGI", "OGGI"]) {
+            if self.is_at(0, &["VAN ", "VON ", "SCH"]) || self.is_at(c + 1, &["ET"]) {
+                self.add("K");
+            } else if self.is_at(c + 1, &["IER "]) {
+                self.add("J");
+            } else {
+                self.add_both("J", "K");
+            }
+            return 2;
+        }
+
+        self.add("K");
+        if next == 'G' {
+            2
+        } else {
+            1
+        }
+    }
+
+    fn encode_s(&mut self, c: isize) -> isize {
+        let last = self.chars.len() as isize - 1;
+
+        if self.is_at(c - 1, &["ISL", "YSL"]) {
+            return 1;
+        }
+        if c == 0 && self.is_at(c, &["SUGAR"]) {
+            self.add_both("X", "S");
+            return 1;
+        }
+        if self.is_at(c, &["SH"]) {
+            if self.is_at(c + 1, &["HEIM", "HOEK", "HOLM", "HOLZ"]) {
+                self.add("S");
+            } else {
+                self.add("X");
+            }
+            return 2;
+        }
+        if self.is_at(c, &["SIO", "SIA", "SIAN"]) {
+            if self.slavo_germanic {
+                self.add("S");
+            } else {
+                self.add_both("S", "X");
+            }
+            return 3;
+        }
+        if (c == 0 && self.is_at(c + 1, &["M", "N", "L", "W"])) || self.is_at(c + 1, &["Z"]) {
+            self.add_both("S", "X");
+            return if self.is_at(c + 1, &["Z"]) { 2 } else { 1 };
+        }
+        if self.is_at(c, &["SC"]) {
+            if self.at(c + 2) == 'H' {
+                if self.is_at(c + 3, &["OO", "ER", "EN", "UY", "ED", "EM"]) {
+                    if self.is_at(c + 3, &["ER", "EN"]) {
+                        self.add_both("X", "SK");
+                    } else {
+                        self.add("SK");
+                    }
+                } else if c == 0 && !self.is_vowel(3) && self.at(3) != 'W' {
+                    self.add_both("X", "S");
+                } else {
+                    self.add("X");
+                }
+            } else if self.is_at(c + 2, &["I", "E", "Y"]) {
+                self.add("S");
+            } else {
+                self.add("SK");
+            }
+            return 3;
+        }
+
+        if c == last && self.is_at(c - 2, &["AI", "OI"]) {
+            self.add_both("", "S");
+        } else {
+            self.add("S");
+        }
+        if self.is_at(c + 1, &["S", "Z"]) {
+            2
+        } else {
+            1
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn primary(word: &str) -> String {
+        double_metaphone(word).0
+    }
+
+    #[test]
+    fn similar_names_share_codes() {
+        assert_eq!(double_metaphone("Smith"), ("SM0".into(), "XMT".into()));
+        assert_eq!(double_metaphone("Smyth"), double_metaphone("Smith"));
+        assert_eq!(primary("John"), "JN");
+        assert_eq!(primary("Jon"), "JN");
+        assert_eq!(primary("Catherine"), primary("Kathryn"));
+        assert_eq!(primary("Philip"), primary("Filip"));
+    }
+
+    #[test]
+    fn alternate_codes_cover_other_pronunciations() {
+        assert_eq!(double_metaphone("Schmidt"), ("XMT".into(), "SMT".into()));
+        assert_eq!(double_metaphone("Jose"), ("JS".into(), "HS".into()));
+        assert_eq!(double_metaphone("Xavier"), ("SF".into(), "SFR".into()));
+        assert_eq!(primary("Knight"), "NT");
+        assert_eq!(primary("Caesar"), "SSR");
+    }
+
+    #[test]
+    fn codes_are_truncated() {
+        assert_eq!(primary("Maximilian"), "MKSM");
+        assert!(codes("").is_empty());
+    }
+
+    #[test]
+    fn words_are_alphabetic_runs() {
+        assert_eq!(
+            words("Jon Smyth-Jones, 3rd"),
+            vec![0..3, 4..9, 10..15, 18..20]
+        );
+    }
+}
@@ -0,0 +1,219 @@
+//! # Prediction Module
+//!
+//! Predicts the next word from the words already typed, using the n-grams of the phrases in
+//! the phrase book. Contexts never seen in a phrase back off to shorter ones, each step
+//! discounting the score, down to the frequency of the words on their own.
+//!
+//! ## Example
+//!
+//! ```
+//! use you_autocomplete_me::{TrieNode, YouAutoCompleteMe};
+//! let mut phrase_book = TrieNode::default().with_next_word_prediction(3);
+//! phrase_book.insert("I don't think I'll pass the science test");
+//! phrase_book.insert("pass the salt");
+//! phrase_book.insert("pass the science exam");
+//! let completer = YouAutoCompleteMe::new(&phrase_book);
+//!
+//! let predictions = completer.predict("pass the ");
+//! assert_eq!(predictions[0].word, "science");
+//! assert_eq!(predictions[1].word, "salt");
+//!
+//! // the last word is completed while it is being typed
+//! assert_eq!(completer.predict("pass the sa")[0].word, "salt");
+//! ```
+
+use std::collections::HashMap;
+
+/// The discount for every word dropped from the context
+const BACKOFF: f64 = 0.4;
+
+/// A predicted next word
+#[derive(Debug, Clone, PartialEq)]
+pub struct Prediction<'a> {
+    /// The predicted word, lowercased
+    pub word: &'a str,
+    /// How often the word follows the context relative to the other words following it,
+    /// discounted when backing off to a shorter context
+    pub score: f64,
+}
+
+/// Counts of the words following every context of up to `order - 1` words
+#[derive(Debug, Default)]
+pub(crate) struct NGrams {
+    order: usize,
+    /// Following words and their counts by the space separated context
+    counts: HashMap<String, HashMap<String, u32>>,
+    /// The texts learned for every phrase, each learned once however often it is inserted
+    learned: HashMap<String, Vec<String>>,
+}
+
+impl NGrams {
+    pub(crate) fn new(order: usize) -> Self {
+        Self {
+            order,
+            counts: HashMap::new(),
+            learned: HashMap::new(),
+        }
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.counts.clear();
+        self.learned.clear();
+    }
+
+    /// Counts the n-grams of the text indexed for the phrase, unless they were already counted
+    pub(crate) fn learn(&mut self, phrase: &str, text: &str) {
+        let learned = self.learned.entry(phrase.to_string()).or_default();
+        if learned.iter().any(|learned| learned == text) {
+            return;
+        }
+        learned.push(text.to_string());
+        for (context, word) in self.ngrams(text) {
+            *self
+                .counts
+                .entry(context)
+                .or_default()
+                .entry(word)
+                .or_default() += 1;
+        }
+    }
+
+    /// Stops counting the n-grams of the texts learned for the phrase
+    pub(crate) fn forget(&mut self, phrase: &str) {
+        for text in self.learned.remove(phrase).unwrap_or_default() {
+            for (context, word) in self.ngrams(&text) {
+                let Some(following) = self.counts.get_mut(&context) else {
+                    continue;
+                };
+                if let Some(count) = following.get_mut(&word) {
+                    *count -= 1;
+                    if *count == 0 {
+                        following.remove(&word);
+                    }
+                }
+                if following.is_empty() {
+                    self.counts.remove(&context);
+                }
+            }
+        }
+    }
+
+    /// Every word of the text along with each context of up to `order - 1` words before it
+    fn ngrams(&self, text: &str) -> Vec<(String, String)> {
+        let words = words(text);
+        let mut ngrams = Vec::new();
+        for (i, word) in words.iter().enumerate() {
+            for length in 0..self.order.min(i + 1) {
+                ngrams.push((words[i - length..i].join(" "), word.clone()));
+            }
+        }
+        ngrams
+    }
+
+    /// Words following the text, best first. Unless the text ends in whitespace, its last
+    /// word is still being typed and only words beginning with it are predicted.
+    pub(crate) fn predict(&self, text: &str) -> Vec<Prediction<'_>> {
+        let mut context = words(text);
+        let partial = match text.ends_with(char::is_whitespace) {
+            true => String::new(),
+            false => context.pop().unwrap_or_default(),
+        };
+        let longest = context.len().min(self.order.saturating_sub(1));
+
+        let mut scores: HashMap<&str, f64> = HashMap::new();
+        for length in (0..=longest).rev() {
+            let Some(following) = self
+                .counts
+                .get(&context[context.len() - length..].join(" "))
+            else {
+                continue;
+            };
+            let discount = BACKOFF.powi((longest - length) as i32);
+            let total: u32 = following.values().sum();
+            for (word, &count) in following {
+                if !word.starts_with(&partial) {
+                    continue;
+                }
+                let score = discount * f64::from(count) / f64::from(total);
+                let best = scores.entry(word).or_default();
+                *best = best.max(score);
+            }
+        }
+
+        let mut predictions: Vec<_> = scores
+            .into_iter()
+            .map(|(word, score)| Prediction { word, score })
+            .collect();
+        predictions.sort_by(|a, b| b.score.total_cmp(&a.score).then(a.word.cmp(b.word)));
+        predictions
+    }
+}
+
+/// The lowercased words of the text, without surrounding punctuation
+fn words(text: &str) -> Vec<String> {
+    text.split_whitespace()
+        .map(|word| word.trim_matches(|c: char| !c.is_alphanumeric()))
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn predicted(ngrams: &NGrams, text: &str) -> Vec<(String, f64)> {
+        ngrams
+            .predict(text)
+            .into_iter()
+            .map(|p| (p.word.to_string(), p.score))
+            .collect()
+    }
+
+    #[test]
+    fn predict_backs_off_to_shorter_contexts() {
+        let mut ngrams = NGrams::new(3);
+        ngrams.learn("a b c", "a b c");
+        ngrams.learn("x b d", "x b d");
+
+        let words =
+            |text| -> Vec<&str> { ngrams.predict(text).into_iter().map(|p| p.word).collect() };
+        assert_eq!(
+            predicted(&ngrams, "a b ")[..2],
+            [("c".into(), 1.0), ("d".into(), 0.2)]
+        );
+        assert_eq!(
+            predicted(&ngrams, "z b ")[..2],
+            [("c".into(), 0.2), ("d".into(), 0.2)]
+        );
+        assert_eq!(words("z z "), vec!["b", "a", "c", "d", "x"]);
+    }
+
+    #[test]
+    fn predict_completes_the_word_being_typed() {
+        let mut ngrams = NGrams::new(2);
+        ngrams.learn("Pass the salt!", "Pass the salt!");
+        ngrams.learn("pass the science test", "pass the science test");
+
+        assert_eq!(predicted(&ngrams, "PASS TH"), vec![("the".into(), 1.0)]);
+        assert!(predicted(&ngrams, "the q").is_empty());
+    }
+
+    #[test]
+    fn learn_counts_every_text_of_a_phrase_once() {
+        let mut ngrams = NGrams::new(2);
+        ngrams.learn("a b", "a b");
+        ngrams.learn("a b", "a b");
+        ngrams.learn("a c", "a c");
+        assert_eq!(
+            predicted(&ngrams, "a ")[..2],
+            [("b".into(), 0.5), ("c".into(), 0.5)]
+        );
+
+        ngrams.forget("a b");
+        assert_eq!(predicted(&ngrams, "a ")[0], ("c".into(), 1.0));
+        assert!(predicted(&ngrams, "b").is_empty());
+        ngrams.forget("a c");
+        assert!(ngrams.counts.is_empty());
+    }
+}
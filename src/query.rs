@@ -0,0 +1,428 @@
+//! # Query Module
+//!
+//! This module parses the fzf style extended search syntax used when [Search::extended] is set.
+//!
+//! ## Syntax
+//!
+//! | Token     | Match type     | Description                              |
+//! | --------- | -------------- | ---------------------------------------- |
+//! | `sbtrkt`  | plain          | matched using the options of the search  |
+//! | `'wild`   | exact          | contains `wild`                          |
+//! | `^music`  | prefix         | starts with `music`                      |
+//! | `.mp3$`   | suffix         | ends with `.mp3`                         |
+//! | `^core$`  | equal          | is exactly `core`                        |
+//! | `!fire`   | inverse exact  | does not contain `fire`                  |
+//! | `!^music` | inverse prefix | does not start with `music`              |
+//! | `!.mp3$`  | inverse suffix | does not end with `.mp3`                 |
+//!
+//! Space separated terms must all match, a `|` between terms matches either of them.
+//!
+//! ## Example
+//!
+//! ```
+//! use you_autocomplete_me::query::{Query, TermKind};
+//! use you_autocomplete_me::search::Search;
+//! let search = Search::builder()
+//!     .search("^core go$ | rb$ !test")
+//!     .extended(true)
+//!     .build();
+//! let query = Query::parse(&search);
+//!
+//! assert_eq!(query.groups().len(), 3);
+//! assert_eq!(query.groups()[1].len(), 2);
+//! assert_eq!(query.groups()[0][0].kind, TermKind::Prefix);
+//! assert!(query.groups()[2][0].negated);
+//! ```
+
+use std::collections::HashSet;
+
+use crate::analyzer::Analysis;
+use crate::boundary::WordBoundaries;
+use crate::cancel::Interrupt;
+use crate::filter::Filter;
+use crate::score::{self, ScoreExplanation};
+use crate::score_configuration::ScoreConfiguration;
+use crate::search::Search;
+use crate::trie::TrieNode;
+
+/// How a [Term] is matched against a phrase
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TermKind {
+    /// Matched using the options of the [Search]
+    Plain,
+    /// The phrase contains the term
+    Exact,
+    /// The phrase starts with the term
+    Prefix,
+    /// The phrase ends with the term
+    Suffix,
+    /// The phrase is the term
+    Equal,
+}
+
+/// A single term of a [Query]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Term<'a> {
+    /// The text of the term with the syntax characters removed
+    pub text: &'a str,
+    /// How the term is matched
+    pub kind: TermKind,
+    /// Whether the term must not match
+    pub negated: bool,
+    /// The alias the term was expanded from, see [Aliases](crate::alias::Aliases)
+    pub alias: Option<&'a str>,
+}
+
+impl<'a> Term<'a> {
+    /// Parses a single whitespace free token, `None` if nothing is left to match
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use you_autocomplete_me::query::{Term, TermKind};
+    /// let term = Term::parse("!.mp3$").unwrap();
+    /// assert_eq!(term.text, ".mp3");
+    /// assert_eq!(term.kind, TermKind::Suffix);
+    /// assert!(term.negated);
+    /// ```
+    pub fn parse(token: &'a str) -> Option<Self> {
+        let (negated, token) = strip_prefix(token, '!');
+        let (exact, token) = strip_prefix(token, '\'');
+        let (prefix, token) = strip_prefix(token, '^');
+        let (suffix, token) = match token.strip_suffix('$') {
+            Some(stripped) => (true, stripped),
+            None => (false, token),
+        };
+        if token.is_empty() {
+            return None;
+        }
+
+        let kind = match (prefix, suffix) {
+            (true, true) => TermKind::Equal,
+            (true, false) => TermKind::Prefix,
+            (false, true) => TermKind::Suffix,
+            _ if exact || negated => TermKind::Exact,
+            _ => TermKind::Plain,
+        };
+        Some(Self {
+            text: token,
+            kind,
+            negated,
+            alias: None,
+        })
+    }
+
+    /// Scores the term against the phrase, `None` if the term does not match.
+    /// Negated terms which match contribute nothing to the score.
+    fn explain(
+        &self,
+        config: &ScoreConfiguration,
+        analysis: Analysis<'_>,
+        search: &Search<'_>,
+        phrase: &str,
+    ) -> Option<ScoreExplanation> {
+        let explanation = match self.kind {
+            TermKind::Plain => score::explain(
+                config,
+                analysis,
+                &Search {
+                    search: self.text,
+                    extended: false,
+                    ..search.clone()
+                },
+                phrase,
+            ),
+            _ => self.explain_anchored(config, analysis, search, phrase),
+        };
+
+        match (explanation, self.negated) {
+            (Some(mut explanation), false) => {
+                if let Some(alias) = self.alias {
+                    explanation.alias = Some(alias.to_string());
+                }
+                Some(explanation)
+            }
+            (None, true) => Some(ScoreExplanation::default()),
+            _ => None,
+        }
+    }
+
+    fn explain_anchored(
+        &self,
+        config: &ScoreConfiguration,
+        analysis: Analysis<'_>,
+        search: &Search<'_>,
+        phrase: &str,
+    ) -> Option<ScoreExplanation> {
+        let fold = !(search.strict_case || self.text.chars().any(|c| c.is_uppercase()));
+        let term = analysis.search(self.text, fold);
+        analysis
+            .phrase(phrase, fold)
+            .into_iter()
+            .filter_map(|text| {
+                let mut candidates = score::substring_positions(&term.chars, &text.chars);
+                candidates.retain(|positions| {
+                    let starts = positions.first() == Some(&0);
+                    let ends = positions.last().map(|p| p + 1) == Some(text.chars.len());
+                    match self.kind {
+                        TermKind::Prefix => starts,
+                        TermKind::Suffix => ends,
+                        TermKind::Equal => starts && ends,
+                        _ => true,
+                    }
+                });
+
+                let boundaries =
+                    WordBoundaries::analyzed(config, analysis.stop_words(), phrase, &text);
+                score::best_explanation(config, &boundaries, candidates)
+                    .map(|e| e.into_original(phrase, &text))
+            })
+            .reduce(|best, e| if e.score > best.score { e } else { best })
+    }
+
+    /// Phrases which may match the term
+    fn candidates<'b, P>(
+        &self,
+        phrase_book: &'b TrieNode<P>,
+        filter: Option<&Filter>,
+        interrupt: &Interrupt<'_>,
+    ) -> HashSet<&'b str> {
+        let search = |value: &str| phrase_book.interruptible_search(value, filter, interrupt);
+        match (self.negated, self.kind) {
+            (true, _) => search(""),
+            (false, TermKind::Plain) => {
+                phrase_book.first_character_search(self.text, filter, interrupt)
+            }
+            (false, _) => search(self.text),
+        }
+    }
+}
+
+fn strip_prefix(token: &str, prefix: char) -> (bool, &str) {
+    match token.strip_prefix(prefix) {
+        Some(stripped) => (true, stripped),
+        None => (false, token),
+    }
+}
+
+/// A parsed extended search.
+/// Every group must match, and a group matches when any of its terms match.
+#[derive(Debug)]
+pub struct Query<'a> {
+    search: Search<'a>,
+    groups: Vec<Vec<Term<'a>>>,
+}
+
+impl<'a> Query<'a> {
+    /// Parses the extended syntax of the search value
+    pub fn parse(search: &Search<'a>) -> Self {
+        let mut groups: Vec<Vec<Term<'a>>> = Vec::new();
+        let mut or_next = false;
+        for token in search.search.split_whitespace() {
+            if token == "|" {
+                or_next = !groups.is_empty();
+                continue;
+            }
+            let Some(term) = Term::parse(token) else {
+                continue;
+            };
+            match groups.last_mut() {
+                Some(group) if or_next => group.push(term),
+                _ => groups.push(vec![term]),
+            }
+            or_next = false;
+        }
+
+        Self {
+            search: search.clone(),
+            groups,
+        }
+    }
+
+    /// A query matching every whitespace token of the search as a plain term
+    pub(crate) fn tokens(search: &Search<'a>) -> Self {
+        let groups = search
+            .search
+            .split_whitespace()
+            .map(|token| {
+                vec![Term {
+                    text: token,
+                    kind: TermKind::Plain,
+                    negated: false,
+                    alias: None,
+                }]
+            })
+            .collect();
+        Self {
+            search: search.clone(),
+            groups,
+        }
+    }
+
+    /// Adds the alternatives of every term to the group of the term
+    pub(crate) fn with_alternatives(
+        mut self,
+        alternatives: impl Fn(&Term<'a>) -> Vec<Term<'a>>,
+    ) -> Self {
+        for group in &mut self.groups {
+            let expanded: Vec<Term<'a>> = group.iter().flat_map(&alternatives).collect();
+            group.extend(expanded);
+        }
+        self
+    }
+
+    /// The groups of terms, every group must match
+    pub fn groups(&self) -> &[Vec<Term<'a>>] {
+        &self.groups
+    }
+
+    /// Scores the phrase against every group, `None` if any group does not match
+    pub(crate) fn explain(
+        &self,
+        config: &ScoreConfiguration,
+        analysis: Analysis<'_>,
+        phrase: &str,
+    ) -> Option<ScoreExplanation> {
+        let mut explanation = ScoreExplanation::default();
+        for group in &self.groups {
+            let best = group
+                .iter()
+                .filter_map(|term| term.explain(config, analysis, &self.search, phrase))
+                .reduce(|best, e| if e.score > best.score { e } else { best })?;
+            explanation.merge(best);
+        }
+        Some(explanation)
+    }
+
+    /// Phrases which may match the query, narrowed using the trie for each group
+    pub(crate) fn candidates<'b, P>(
+        &self,
+        phrase_book: &'b TrieNode<P>,
+        interrupt: &Interrupt<'_>,
+    ) -> HashSet<&'b str> {
+        let filter = self.search.filter.as_deref();
+        self.groups
+            .iter()
+            .map(|group| {
+                group
+                    .iter()
+                    .flat_map(|term| term.candidates(phrase_book, filter, interrupt))
+                    .collect::<HashSet<_>>()
+            })
+            .reduce(|a, b| a.intersection(&b).copied().collect())
+            .unwrap_or_else(|| phrase_book.interruptible_search("", filter, interrupt))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::normalize::Normalizer;
+
+    fn query(input: &str) -> Query<'_> {
+        Query::parse(&Search::builder().search(input).extended(true).build())
+    }
+
+    fn matches(input: &str, phrase: &str) -> bool {
+        query(input)
+            .explain(
+                &ScoreConfiguration::default(),
+                Analysis::new(&Normalizer::default()),
+                phrase,
+            )
+            .is_some()
+    }
+
+    #[test]
+    fn parse_term_kinds() {
+        let kinds: Vec<(TermKind, bool)> = query("plain 'exact ^prefix suffix$ ^equal$ !not")
+            .groups()
+            .iter()
+            .map(|group| (group[0].kind, group[0].negated))
+            .collect();
+
+        assert_eq!(
+            kinds,
+            vec![
+                (TermKind::Plain, false),
+                (TermKind::Exact, false),
+                (TermKind::Prefix, false),
+                (TermKind::Suffix, false),
+                (TermKind::Equal, false),
+                (TermKind::Exact, true),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_ignores_empty_terms_and_dangling_or() {
+        let query = query("| ^ $ ! a | | b |");
+        let texts: Vec<Vec<&str>> = query
+            .groups()
+            .iter()
+            .map(|group| group.iter().map(|term| term.text).collect())
+            .collect();
+
+        assert_eq!(texts, vec![vec!["a", "b"]]);
+    }
+
+    #[test]
+    fn anchored_terms_match_phrase_ends() {
+        assert!(matches("^hello", "hello-world"));
+        assert!(!matches("^world", "hello-world"));
+        assert!(matches("world$", "hello-world"));
+        assert!(!matches("hello$", "hello-world"));
+        assert!(matches("^hello-world$", "hello-world"));
+        assert!(!matches("^hello$", "hello-world"));
+        assert!(matches("'lo-wo", "hello-world"));
+        assert!(!matches("'hw", "hello-world"));
+        assert!(matches("hw", "hello-world"));
+    }
+
+    #[test]
+    fn every_group_must_match() {
+        assert!(matches("^hello world$", "hello-world"));
+        assert!(!matches("^hello moon$", "hello-world"));
+        assert!(matches("^hello moon$ | world$", "hello-world"));
+    }
+
+    #[test]
+    fn negated_terms_exclude_phrases() {
+        assert!(!matches("!world", "hello-world"));
+        assert!(matches("!moon", "hello-world"));
+        assert!(matches("hello !^world", "hello-world"));
+        assert!(!matches("hello !.mp3$ !-world$", "hello-world"));
+    }
+
+    #[test]
+    fn scores_are_summed_across_groups() {
+        let config = ScoreConfiguration::default();
+        let normalizer = Normalizer::default();
+        let explain =
+            |input| query(input).explain(&config, Analysis::new(&normalizer), "hello-world");
+        let hello = explain("^hello").unwrap();
+        let world = explain("world$").unwrap();
+        let both = explain("^hello world$").unwrap();
+
+        assert_eq!(both.score, hello.score + world.score);
+        assert_eq!(
+            both.positions,
+            (0..11).filter(|&i| i != 5).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn candidates_are_narrowed_by_the_trie() {
+        let mut phrase_book = TrieNode::default();
+        for phrase in ["hello-world", "help", "world", "spark"] {
+            phrase_book.insert(phrase);
+        }
+
+        let mut candidates: Vec<_> = query("^hel | spa !rk")
+            .candidates(&phrase_book, &Interrupt::never())
+            .into_iter()
+            .collect();
+        candidates.sort();
+        assert_eq!(candidates, vec!["hello-world", "help", "spark"]);
+    }
+}
@@ -0,0 +1,994 @@
+//! # Score Module
+//!
+//! Scores a phrase against a [Search] using a [ScoreConfiguration] and records which parts of
+//! the configuration contributed to the final score.
+//!
+//! ## Example
+//!
+//! ```
+//! use you_autocomplete_me::score::ScoreComponent;
+//! use you_autocomplete_me::{TrieNode, YouAutoCompleteMe};
+//! let phrase_book = TrieNode::default();
+//! let completer = YouAutoCompleteMe::new(&phrase_book);
+//!
+//! let explanation = completer.explain("wor", "world").unwrap();
+//! assert_eq!(explanation.positions, vec![0, 1, 2]);
+//! assert_eq!(explanation.total(ScoreComponent::WordPrefix), 3);
+//! assert_eq!(explanation.score, 11);
+//! ```
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::ops::Range;
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+use crate::analyzer::{Analysis, AnalyzedText};
+use crate::boundary::{grapheme_starts, WordBoundaries};
+use crate::cancel::Interrupt;
+use crate::phonetic;
+use crate::query::Query;
+use crate::score_configuration::ScoreConfiguration;
+use crate::search::Search;
+
+/// A part of the [ScoreConfiguration] which can contribute to a score
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ScoreComponent {
+    /// Bonus for a matched character directly following another matched character
+    CharacterAdjacency,
+    /// Bonus for a run of matched characters starting at the beginning of a word
+    WordBoundary,
+    /// Bonus for the match starting at the beginning of the phrase
+    WordPrefix,
+    /// Bonus for the match ending at the end of a word
+    WordSuffix,
+    /// Penalty for the match not starting at the beginning of the phrase
+    CharacterOffset,
+    /// Bonus for the search matching word initials in acronym mode
+    Acronym,
+    /// Penalty for a search word which only sounds like a word of the phrase
+    Phonetic,
+}
+
+impl fmt::Display for ScoreComponent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ScoreComponent::CharacterAdjacency => "character adjacency",
+            ScoreComponent::WordBoundary => "word boundary",
+            ScoreComponent::WordPrefix => "word prefix",
+            ScoreComponent::WordSuffix => "word suffix",
+            ScoreComponent::CharacterOffset => "character offset",
+            ScoreComponent::Acronym => "acronym",
+            ScoreComponent::Phonetic => "phonetic",
+        };
+        f.write_str(name)
+    }
+}
+
+/// A single [ScoreComponent] which fired while scoring a phrase
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScoreContribution {
+    /// The component which fired
+    pub component: ScoreComponent,
+    /// The character position in the phrase the component fired at
+    pub position: usize,
+    /// The amount added to the score, negative for penalties
+    pub value: i32,
+}
+
+/// A breakdown of how a phrase was scored against a query
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScoreExplanation {
+    /// The character positions in the phrase which matched the query
+    pub positions: Vec<usize>,
+    /// Every component which fired, in the order they were applied
+    pub contributions: Vec<ScoreContribution>,
+    /// The final score
+    pub score: i32,
+    /// Whether the phrase only matched by sound. Phonetic matches rank below every spelled
+    /// match, whatever their scores.
+    pub phonetic: bool,
+    /// The first alias which expanded the search into the match, see
+    /// [Aliases](crate::alias::Aliases)
+    pub alias: Option<String>,
+}
+
+impl ScoreExplanation {
+    /// The summed contribution of a single [ScoreComponent]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use you_autocomplete_me::score::ScoreComponent;
+    /// use you_autocomplete_me::{TrieNode, YouAutoCompleteMe};
+    /// let phrase_book = TrieNode::default();
+    /// let completer = YouAutoCompleteMe::new(&phrase_book);
+    ///
+    /// let explanation = completer.explain("wor", "hello-world").unwrap();
+    /// assert_eq!(explanation.total(ScoreComponent::CharacterOffset), -3);
+    /// ```
+    pub fn total(&self, component: ScoreComponent) -> i32 {
+        self.contributions
+            .iter()
+            .filter(|c| c.component == component)
+            .map(|c| c.value)
+            .sum()
+    }
+
+    /// Adds the positions and contributions of another explanation to this one
+    pub(crate) fn merge(&mut self, other: ScoreExplanation) {
+        self.positions.extend(other.positions);
+        self.positions.sort_unstable();
+        self.positions.dedup();
+        self.contributions.extend(other.contributions);
+        self.score += other.score;
+        self.phonetic |= other.phonetic;
+        self.alias = self.alias.take().or(other.alias);
+    }
+
+    /// Maps positions in the analyzed text back to the original phrase, covering the whole
+    /// grapheme cluster of every matched character
+    pub(crate) fn into_original(mut self, phrase: &str, text: &AnalyzedText) -> Self {
+        let graphemes = grapheme_starts(phrase);
+        self.positions = self
+            .positions
+            .iter()
+            .flat_map(|&position| {
+                let origin = text.origins[position];
+                let end = (origin + 1..graphemes.len())
+                    .find(|&i| graphemes[i])
+                    .unwrap_or(origin + 1);
+                origin..end
+            })
+            .collect();
+        self.positions.sort_unstable();
+        self.positions.dedup();
+        for contribution in &mut self.contributions {
+            contribution.position = text.origins[contribution.position];
+        }
+        self
+    }
+
+    fn push(&mut self, component: ScoreComponent, position: usize, value: i32) {
+        if value != 0 {
+            self.score += value;
+            self.contributions.push(ScoreContribution {
+                component,
+                position,
+                value,
+            });
+        }
+    }
+}
+
+impl fmt::Display for ScoreExplanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "score {} at {:?}", self.score, self.positions)?;
+        for contribution in &self.contributions {
+            writeln!(
+                f,
+                "  {:+} {} at {}",
+                contribution.value, contribution.component, contribution.position
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// What scoring needs from an auto completer, free of payloads so that it can be shared
+/// between threads
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Scorer<'c, 'a> {
+    pub(crate) config: &'c ScoreConfiguration,
+    pub(crate) analysis: Analysis<'a>,
+    /// The named fields of the phrases inserted as records
+    pub(crate) records: &'a HashMap<String, Vec<Field>>,
+}
+
+/// The name and text of a field of a record
+type Field = (String, String);
+
+impl<'a> Scorer<'_, 'a> {
+    /// Explains the text with the extended query, if any, otherwise with the search
+    pub(crate) fn explain(
+        &self,
+        search: &Search<'_>,
+        query: Option<&Query<'_>>,
+        text: &str,
+    ) -> Option<ScoreExplanation> {
+        match query {
+            Some(query) => query.explain(self.config, self.analysis, text),
+            None => explain(self.config, self.analysis, search, text),
+        }
+    }
+
+    /// Explains the phrase, or for records the weighted sum of its matching fields along
+    /// with the positions, name and text of the best of them. A record only matches by sound when
+    /// every matching field does.
+    pub(crate) fn explain_phrase(
+        &self,
+        search: &Search<'_>,
+        query: Option<&Query<'_>>,
+        phrase: &str,
+    ) -> Option<(ScoreExplanation, Option<&'a Field>)> {
+        let Some(fields) = self.records.get(phrase) else {
+            return self
+                .explain(search, query, phrase)
+                .map(|explanation| (explanation, None));
+        };
+
+        let weighted = fields.iter().filter_map(|field| {
+            let (name, text) = field;
+            let explanation = self.explain(search, query, text)?;
+            let weight = self.config.field_weights.get(name).copied().unwrap_or(1);
+            Some((explanation.score * i32::from(weight), explanation, field))
+        });
+        let mut total = 0;
+        let mut phonetic = true;
+        let mut best: Option<(i32, ScoreExplanation, &'a Field)> = None;
+        for (score, explanation, field) in weighted {
+            total += score;
+            phonetic &= explanation.phonetic;
+            if best.as_ref().is_none_or(|(best, ..)| score > *best) {
+                best = Some((score, explanation, field));
+            }
+        }
+        best.map(|(_, mut explanation, field)| {
+            explanation.score = total;
+            explanation.phonetic = phonetic;
+            (explanation, Some(field))
+        })
+    }
+
+    /// Explains every candidate until interrupted, keeping those which match. With the
+    /// `rayon` feature the candidates are explained in parallel.
+    pub(crate) fn explain_candidates<'p>(
+        &self,
+        search: &Search<'_>,
+        query: Option<&Query<'_>>,
+        candidates: HashSet<&'p str>,
+        interrupt: &Interrupt<'_>,
+    ) -> Vec<(&'p str, ScoreExplanation, Option<&'a Field>)> {
+        let explain = |phrase: &'p str| {
+            if interrupt.check() {
+                return None;
+            }
+            let (explanation, field) = self.explain_phrase(search, query, phrase)?;
+            Some((phrase, explanation, field))
+        };
+        #[cfg(feature = "rayon")]
+        let explained = candidates.into_par_iter().filter_map(explain).collect();
+        #[cfg(not(feature = "rayon"))]
+        let explained = candidates.into_iter().filter_map(explain).collect();
+        explained
+    }
+}
+
+/// Scores the best match of the search within the phrase.
+/// Searches containing whitespace also match when every token matches, in any order.
+/// Phonetic searches also match when every token sounds like a word of the phrase, unless the
+/// phrase is spelled like the search.
+/// Returns `None` if the phrase does not match.
+pub(crate) fn explain(
+    config: &ScoreConfiguration,
+    analysis: Analysis<'_>,
+    search: &Search<'_>,
+    phrase: &str,
+) -> Option<ScoreExplanation> {
+    let spelled = explain_spelled(config, analysis, search, phrase);
+    match search.phonetic {
+        true => spelled.or_else(|| explain_phonetic(config, search, phrase)),
+        false => spelled,
+    }
+}
+
+fn explain_spelled(
+    config: &ScoreConfiguration,
+    analysis: Analysis<'_>,
+    search: &Search<'_>,
+    phrase: &str,
+) -> Option<ScoreExplanation> {
+    let whole = explain_term(config, analysis, search, phrase);
+    if search.search.split_whitespace().nth(1).is_none() {
+        return whole;
+    }
+
+    let tokens = search.search.split_whitespace().try_fold(
+        ScoreExplanation::default(),
+        |mut explanation, token| {
+            let token = Search {
+                search: token,
+                ..search.clone()
+            };
+            explanation.merge(explain_term(config, analysis, &token, phrase)?);
+            Some(explanation)
+        },
+    );
+
+    better(whole, tokens)
+}
+
+/// The higher scoring explanation, preferring the first on a tie
+fn better(
+    first: Option<ScoreExplanation>,
+    second: Option<ScoreExplanation>,
+) -> Option<ScoreExplanation> {
+    match (first, second) {
+        (Some(first), Some(second)) if second.score > first.score => Some(second),
+        (Some(first), _) => Some(first),
+        (None, second) => second,
+    }
+}
+
+/// Scores the phrase words which sound like the search tokens, every token must sound like a
+/// word of the phrase. Matched words are scored as whole words, along with the phonetic penalty.
+fn explain_phonetic(
+    config: &ScoreConfiguration,
+    search: &Search<'_>,
+    phrase: &str,
+) -> Option<ScoreExplanation> {
+    let chars: Vec<char> = phrase.chars().collect();
+    let boundaries = WordBoundaries::new(config, &chars);
+    let words: Vec<(Range<usize>, Vec<String>)> = phonetic::words(phrase)
+        .into_iter()
+        .map(|word| {
+            let codes = phonetic::codes(&chars[word.clone()].iter().collect::<String>());
+            (word, codes)
+        })
+        .collect();
+
+    let mut tokens = search.search.split_whitespace().peekable();
+    tokens.peek()?;
+    let explanation = ScoreExplanation {
+        phonetic: true,
+        ..Default::default()
+    };
+    tokens.try_fold(explanation, |mut explanation, token| {
+        let token_codes = phonetic::codes(token);
+        let candidates = words
+            .iter()
+            .filter(|(_, codes)| codes.iter().any(|code| token_codes.contains(code)))
+            .map(|(word, _)| word.clone().collect())
+            .collect();
+        let mut best = best_explanation(config, &boundaries, candidates)?;
+        best.push(
+            ScoreComponent::Phonetic,
+            best.positions[0],
+            -i32::from(config.phonetic_penalty),
+        );
+        explanation.merge(best);
+        Some(explanation)
+    })
+}
+
+/// Scores the best match of the whole search within the phrase
+fn explain_term(
+    config: &ScoreConfiguration,
+    analysis: Analysis<'_>,
+    search: &Search<'_>,
+    phrase: &str,
+) -> Option<ScoreExplanation> {
+    let fold = !(search.strict_case || search.is_smart_case());
+    let query = analysis.search(search.search, fold);
+    analysis
+        .phrase(phrase, fold)
+        .into_iter()
+        .filter_map(|text| {
+            let boundaries = WordBoundaries::analyzed(config, analysis.stop_words(), phrase, &text);
+            explain_analyzed(config, search, &query.chars, &text.chars, &boundaries)
+                .map(|e| e.into_original(phrase, &text))
+        })
+        .reduce(|best, e| if e.score > best.score { e } else { best })
+}
+
+/// Scores the analyzed search against the analyzed phrase, positions are in the analyzed text
+fn explain_analyzed(
+    config: &ScoreConfiguration,
+    search: &Search<'_>,
+    query: &[char],
+    phrase: &[char],
+    boundaries: &WordBoundaries,
+) -> Option<ScoreExplanation> {
+    if search.acronym {
+        let positions = acronym_positions(query, phrase, boundaries)?;
+        let mut explanation = explain_positions(config, boundaries, positions);
+        if let Some(&first) = explanation.positions.first() {
+            explanation.push(
+                ScoreComponent::Acronym,
+                first,
+                i32::from(config.acronym_bonus),
+            );
+        }
+        return Some(explanation);
+    }
+
+    let mut candidates = substring_positions(query, phrase);
+    if search.word_prefix {
+        candidates.retain(|positions| boundaries.runs_start_at_words(positions));
+    }
+    if candidates.is_empty() {
+        candidates.extend(WordPrefixAligner::new(query, phrase, boundaries).align());
+    }
+    if candidates.is_empty() && search.fuzzy && !search.word_prefix {
+        candidates.extend(subsequence_positions(query, phrase));
+        candidates.extend(boundary_subsequence_positions(query, phrase, boundaries));
+    }
+
+    best_explanation(config, boundaries, candidates)
+}
+
+/// Scores every candidate set of positions, keeping the highest score
+pub(crate) fn best_explanation(
+    config: &ScoreConfiguration,
+    boundaries: &WordBoundaries,
+    candidates: Vec<Vec<usize>>,
+) -> Option<ScoreExplanation> {
+    candidates
+        .into_iter()
+        .filter(|positions| {
+            boundaries.runs_cover_graphemes(positions) && boundaries.runs_start_unskipped(positions)
+        })
+        .map(|positions| explain_positions(config, boundaries, positions))
+        .reduce(|best, e| if e.score > best.score { e } else { best })
+}
+
+fn explain_positions(
+    config: &ScoreConfiguration,
+    boundaries: &WordBoundaries,
+    positions: Vec<usize>,
+) -> ScoreExplanation {
+    let mut explanation = ScoreExplanation::default();
+
+    let mut run: u32 = 0;
+    let mut previous: Option<usize> = None;
+    for &position in &positions {
+        let adjacent = previous.is_some_and(|p| p + 1 == position);
+        if adjacent {
+            run += 1;
+            let bonus = i32::from(config.character_adjacency_bonus).saturating_mul(
+                i32::from(config.character_adjacency_multiplier).saturating_pow(run - 1),
+            );
+            explanation.push(
+                ScoreComponent::CharacterAdjacency,
+                position,
+                bonus.min(i32::from(config.max_character_adjacency_bonus)),
+            );
+        } else {
+            run = 0;
+            if boundaries.is_start(position) && !boundaries.is_stop_word(position) {
+                explanation.push(
+                    ScoreComponent::WordBoundary,
+                    position,
+                    i32::from(config.word_boundary_bonus),
+                );
+            }
+        }
+        previous = Some(position);
+    }
+
+    if let (Some(&first), Some(&last)) = (positions.first(), positions.last()) {
+        if first == 0 {
+            explanation.push(
+                ScoreComponent::WordPrefix,
+                first,
+                i32::from(config.word_prefix_bonus),
+            );
+        }
+        if boundaries.is_end(last) {
+            explanation.push(
+                ScoreComponent::WordSuffix,
+                last,
+                i32::from(config.word_suffix_bonus),
+            );
+        }
+        let offset = i32::try_from(first).unwrap_or(i32::MAX);
+        let penalty = offset
+            .saturating_mul(i32::from(config.character_offset_penalty))
+            .min(i32::from(config.max_offset_penalty));
+        explanation.push(ScoreComponent::CharacterOffset, first, -penalty);
+    }
+
+    explanation.positions = positions;
+    explanation
+}
+
+/// Every contiguous occurrence of the query within the phrase
+pub(crate) fn substring_positions(query: &[char], phrase: &[char]) -> Vec<Vec<usize>> {
+    if query.is_empty() {
+        return vec![Vec::new()];
+    }
+    if query.len() > phrase.len() {
+        return Vec::new();
+    }
+    (0..=phrase.len() - query.len())
+        .filter(|&start| phrase[start..].starts_with(query))
+        .map(|start| (start..start + query.len()).collect())
+        .collect()
+}
+
+/// The leftmost occurrence of the query characters, in order, within the phrase
+fn subsequence_positions(query: &[char], phrase: &[char]) -> Option<Vec<usize>> {
+    let mut positions = Vec::with_capacity(query.len());
+    let mut chars = phrase.iter().enumerate();
+    for &q in query {
+        let (position, _) = chars.find(|(_, &p)| p == q)?;
+        positions.push(position);
+    }
+    Some(positions)
+}
+
+/// The query characters, in order, within the phrase preferring characters which begin a word
+fn boundary_subsequence_positions(
+    query: &[char],
+    phrase: &[char],
+    boundaries: &WordBoundaries,
+) -> Option<Vec<usize>> {
+    let mut positions = Vec::with_capacity(query.len());
+    let mut from = 0;
+    for &q in query {
+        let matches = |i: &usize| phrase[*i] == q;
+        let position = (from..phrase.len())
+            .filter(|&i| boundaries.is_start(i))
+            .find(matches)
+            .or_else(|| (from..phrase.len()).find(matches))?;
+        positions.push(position);
+        from = position + 1;
+    }
+    Some(positions)
+}
+
+/// The leftmost word initials matching every query character in order
+fn acronym_positions(
+    query: &[char],
+    phrase: &[char],
+    boundaries: &WordBoundaries,
+) -> Option<Vec<usize>> {
+    let mut initials = boundaries.starts().filter(|&i| !boundaries.is_stop_word(i));
+    query
+        .iter()
+        .map(|&q| initials.find(|&i| phrase[i] == q))
+        .collect()
+}
+
+/// Splits the query across word prefixes, every run of matched characters beginning a word.
+/// "hw" aligns with "hello-world" at the starts of both words.
+struct WordPrefixAligner<'a> {
+    query: &'a [char],
+    phrase: &'a [char],
+    boundaries: &'a WordBoundaries,
+    positions: Vec<usize>,
+    failed: HashSet<(usize, usize, bool)>,
+}
+
+impl<'a> WordPrefixAligner<'a> {
+    fn new(query: &'a [char], phrase: &'a [char], boundaries: &'a WordBoundaries) -> Self {
+        Self {
+            query,
+            phrase,
+            boundaries,
+            positions: Vec::with_capacity(query.len()),
+            failed: HashSet::new(),
+        }
+    }
+
+    fn align(mut self) -> Option<Vec<usize>> {
+        self.step(0, false).then_some(self.positions)
+    }
+
+    /// Matches the next query character at or after `from`, either continuing the current run
+    /// or starting a new one at a word start
+    fn step(&mut self, from: usize, continuing: bool) -> bool {
+        let Some(&q) = self.query.get(self.positions.len()) else {
+            return true;
+        };
+        let key = (self.positions.len(), from, continuing);
+        if self.failed.contains(&key) {
+            return false;
+        }
+
+        let continuation = continuing.then_some(from);
+        let word_starts = self.boundaries.starts().filter(|&start| start >= from);
+        let candidates: Vec<usize> = continuation.into_iter().chain(word_starts).collect();
+        for position in candidates {
+            if self.phrase.get(position) == Some(&q) {
+                self.positions.push(position);
+                if self.step(position + 1, true) {
+                    return true;
+                }
+                self.positions.pop();
+            }
+        }
+
+        self.failed.insert(key);
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::normalize::Normalizer;
+
+    #[test]
+    fn explain_prefix_match_fires_prefix_and_boundary() {
+        let config = ScoreConfiguration::default();
+        let explanation = explain(
+            &config,
+            Analysis::new(&Normalizer::default()),
+            &Search::new("wor"),
+            "world",
+        )
+        .unwrap();
+
+        assert_eq!(explanation.positions, vec![0, 1, 2]);
+        assert_eq!(explanation.total(ScoreComponent::CharacterAdjacency), 3);
+        assert_eq!(explanation.total(ScoreComponent::WordBoundary), 5);
+        assert_eq!(explanation.total(ScoreComponent::WordPrefix), 3);
+        assert_eq!(explanation.total(ScoreComponent::WordSuffix), 0);
+        assert_eq!(explanation.total(ScoreComponent::CharacterOffset), 0);
+        assert_eq!(explanation.score, 11);
+    }
+
+    #[test]
+    fn explain_match_after_delimiter_is_penalized_for_offset() {
+        let config = ScoreConfiguration::default();
+        let explanation = explain(
+            &config,
+            Analysis::new(&Normalizer::default()),
+            &Search::new("wor"),
+            "hello-world",
+        )
+        .unwrap();
+
+        assert_eq!(explanation.positions, vec![6, 7, 8]);
+        assert_eq!(explanation.total(ScoreComponent::WordBoundary), 5);
+        assert_eq!(explanation.total(ScoreComponent::WordPrefix), 0);
+        assert_eq!(explanation.total(ScoreComponent::CharacterOffset), -3);
+        assert_eq!(explanation.score, 5);
+    }
+
+    #[test]
+    fn explain_picks_the_best_occurrence() {
+        let config = ScoreConfiguration::default();
+        let explanation = explain(
+            &config,
+            Analysis::new(&Normalizer::default()),
+            &Search::new("test"),
+            "attest the test",
+        )
+        .unwrap();
+
+        assert_eq!(explanation.positions, vec![11, 12, 13, 14]);
+        assert_eq!(explanation.total(ScoreComponent::WordSuffix), 3);
+    }
+
+    #[test]
+    fn explain_adjacency_bonus_is_capped() {
+        let config = ScoreConfiguration::builder()
+            .with_max_character_adjacency_bonus(3)
+            .build();
+        let explanation = explain(
+            &config,
+            Analysis::new(&Normalizer::default()),
+            &Search::new("abcd"),
+            "abcd",
+        )
+        .unwrap();
+
+        let adjacency: Vec<i32> = explanation
+            .contributions
+            .iter()
+            .filter(|c| c.component == ScoreComponent::CharacterAdjacency)
+            .map(|c| c.value)
+            .collect();
+        assert_eq!(adjacency, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn explain_without_match_is_none() {
+        let config = ScoreConfiguration::default();
+        assert!(explain(
+            &config,
+            Analysis::new(&Normalizer::default()),
+            &Search::new("xyz"),
+            "world"
+        )
+        .is_none());
+        assert!(explain(
+            &config,
+            Analysis::new(&Normalizer::default()),
+            &Search::new("Wor"),
+            "world"
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn explain_matches_characters_at_word_starts() {
+        let config = ScoreConfiguration::default();
+        let explanation = explain(
+            &config,
+            Analysis::new(&Normalizer::default()),
+            &Search::new("hw"),
+            "hello-world",
+        )
+        .unwrap();
+
+        assert_eq!(explanation.positions, vec![0, 6]);
+        assert_eq!(explanation.total(ScoreComponent::WordBoundary), 10);
+        assert_eq!(explanation.score, 13);
+
+        let explanation = explain(
+            &config,
+            Analysis::new(&Normalizer::default()),
+            &Search::new("helwor"),
+            "hello-world",
+        )
+        .unwrap();
+        assert_eq!(explanation.positions, vec![0, 1, 2, 6, 7, 8]);
+    }
+
+    #[test]
+    fn explain_matches_characters_at_camel_case_boundaries() {
+        let config = ScoreConfiguration::default();
+        let explanation = explain(
+            &config,
+            Analysis::new(&Normalizer::default()),
+            &Search::new("hrj"),
+            "parseHttpRequest2Json",
+        )
+        .unwrap();
+
+        assert_eq!(explanation.positions, vec![5, 9, 17]);
+        assert_eq!(explanation.total(ScoreComponent::WordBoundary), 15);
+    }
+
+    #[test]
+    fn explain_acronym_matches_word_initials() {
+        let config = ScoreConfiguration::default();
+        let search = Search::builder().search("gcs").acronym(true).build();
+        let explanation = explain(
+            &config,
+            Analysis::new(&Normalizer::default()),
+            &search,
+            "Google Cloud Storage",
+        )
+        .unwrap();
+
+        assert_eq!(explanation.positions, vec![0, 7, 13]);
+        assert_eq!(explanation.total(ScoreComponent::Acronym), 6);
+
+        let search = Search::builder().search("ndc").acronym(true).build();
+        let explanation = explain(
+            &config,
+            Analysis::new(&Normalizer::default()),
+            &search,
+            "new_document_controller",
+        )
+        .unwrap();
+        assert_eq!(explanation.positions, vec![0, 4, 13]);
+
+        let search = Search::builder().search("phr").acronym(true).build();
+        let explanation = explain(
+            &config,
+            Analysis::new(&Normalizer::default()),
+            &search,
+            "parseHttpRequest",
+        )
+        .unwrap();
+        assert_eq!(explanation.positions, vec![0, 5, 9]);
+    }
+
+    #[test]
+    fn explain_acronym_does_not_match_inside_words() {
+        let config = ScoreConfiguration::default();
+        let search = Search::builder().search("goo").acronym(true).build();
+
+        assert!(explain(
+            &config,
+            Analysis::new(&Normalizer::default()),
+            &search,
+            "Google Cloud Storage"
+        )
+        .is_none());
+        assert!(explain(
+            &config,
+            Analysis::new(&Normalizer::default()),
+            &Search::new("goo"),
+            "Google Cloud Storage"
+        )
+        .is_some());
+    }
+
+    #[test]
+    fn explain_word_prefix_only_rejects_matches_inside_words() {
+        let config = ScoreConfiguration::default();
+        let search = Search::builder().search("orl").word_prefix(true).build();
+        assert!(explain(
+            &config,
+            Analysis::new(&Normalizer::default()),
+            &search,
+            "hello-world"
+        )
+        .is_none());
+
+        let search = Search::builder().search("wo").word_prefix(true).build();
+        let explanation = explain(
+            &config,
+            Analysis::new(&Normalizer::default()),
+            &search,
+            "sword world",
+        )
+        .unwrap();
+        assert_eq!(explanation.positions, vec![6, 7]);
+    }
+
+    #[test]
+    fn explain_fuzzy_prefers_word_starts() {
+        let config = ScoreConfiguration::default();
+        let search = Search::builder().search("hwd").fuzzy(true).build();
+        let explanation = explain(
+            &config,
+            Analysis::new(&Normalizer::default()),
+            &search,
+            "shadow-world",
+        )
+        .unwrap();
+
+        assert_eq!(explanation.positions, vec![1, 7, 11]);
+    }
+
+    #[test]
+    fn explain_matches_tokens_in_any_order() {
+        let config = ScoreConfiguration::default();
+        let phrase = "I don't think I'll pass the science test";
+        let explanation = explain(
+            &config,
+            Analysis::new(&Normalizer::default()),
+            &Search::new("test science"),
+            phrase,
+        )
+        .unwrap();
+
+        let science = explain(
+            &config,
+            Analysis::new(&Normalizer::default()),
+            &Search::new("science"),
+            phrase,
+        )
+        .unwrap();
+        let test = explain(
+            &config,
+            Analysis::new(&Normalizer::default()),
+            &Search::new("test"),
+            phrase,
+        )
+        .unwrap();
+        assert_eq!(explanation.score, science.score + test.score);
+        assert_eq!(explanation.positions.len(), 11);
+
+        assert!(explain(
+            &config,
+            Analysis::new(&Normalizer::default()),
+            &Search::new("test moon"),
+            phrase
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn explain_prefers_the_whole_search_when_it_scores_higher() {
+        let config = ScoreConfiguration::default();
+        let explanation = explain(
+            &config,
+            Analysis::new(&Normalizer::default()),
+            &Search::new("pass the"),
+            "pass the test",
+        )
+        .unwrap();
+
+        assert_eq!(explanation.positions, (0..8).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn explain_fuzzy_falls_back_to_subsequence() {
+        let config = ScoreConfiguration::default();
+        let search = Search::builder().search("wld").fuzzy(true).build();
+        let explanation = explain(
+            &config,
+            Analysis::new(&Normalizer::default()),
+            &search,
+            "world",
+        )
+        .unwrap();
+
+        assert_eq!(explanation.positions, vec![0, 3, 4]);
+    }
+
+    #[test]
+    fn explain_non_ascii_phrases_by_character() {
+        let normalizer = Normalizer::default();
+        let analysis = Analysis::new(&normalizer);
+        let config = ScoreConfiguration::default();
+        let explanation = explain(&config, analysis, &Search::new("ïve"), "naïve").unwrap();
+
+        assert_eq!(explanation.positions, vec![2, 3, 4]);
+        assert_eq!(explanation.total(ScoreComponent::WordSuffix), 3);
+    }
+
+    #[test]
+    fn explain_combining_marks_as_part_of_their_character() {
+        let normalizer = Normalizer::default();
+        let analysis = Analysis::new(&normalizer);
+        let config = ScoreConfiguration::default();
+        let phrase = "q\u{303}uick";
+
+        assert!(explain(&config, analysis, &Search::new("qu"), phrase).is_none());
+        let explanation = explain(&config, analysis, &Search::new("q\u{303}u"), phrase).unwrap();
+        assert_eq!(explanation.positions, vec![0, 1, 2]);
+
+        let fuzzy = Search::builder().search("q").fuzzy(true).build();
+        assert!(explain(&config, analysis, &fuzzy, phrase).is_none());
+    }
+
+    #[test]
+    fn explain_emoji_as_whole_graphemes() {
+        let normalizer = Normalizer::default();
+        let analysis = Analysis::new(&normalizer);
+        let config = ScoreConfiguration::default();
+        let phrase = "👨\u{200d}👩\u{200d}👧 family 🇯🇵";
+
+        assert!(explain(&config, analysis, &Search::new("👨"), phrase).is_none());
+        assert!(explain(&config, analysis, &Search::new("🇯"), phrase).is_none());
+
+        let family = Search::new("👨\u{200d}👩\u{200d}👧");
+        let explanation = explain(&config, analysis, &family, phrase).unwrap();
+        assert_eq!(explanation.positions, vec![0, 1, 2, 3, 4]);
+
+        let explanation = explain(&config, analysis, &Search::new("fam"), phrase).unwrap();
+        assert_eq!(explanation.positions, vec![6, 7, 8]);
+        let explanation = explain(&config, analysis, &Search::new("🇯🇵"), phrase).unwrap();
+        assert_eq!(explanation.positions, vec![13, 14]);
+    }
+
+    #[test]
+    fn explain_phonetic_matches_whole_words_with_a_penalty() {
+        let normalizer = Normalizer::default();
+        let analysis = Analysis::new(&normalizer);
+        let config = ScoreConfiguration::default();
+        let search = Search::builder().search("jon smyth").phonetic(true).build();
+
+        assert!(explain(&config, analysis, &Search::new("jon smyth"), "John Smith").is_none());
+        let explanation = explain(&config, analysis, &search, "John Smith").unwrap();
+        assert_eq!(explanation.positions, vec![0, 1, 2, 3, 5, 6, 7, 8, 9]);
+        assert_eq!(explanation.total(ScoreComponent::Phonetic), -40);
+        assert!(explanation.phonetic);
+
+        let spelled = explain(&config, analysis, &search, "Jon Smyth").unwrap();
+        assert_eq!(spelled.total(ScoreComponent::Phonetic), 0);
+        assert!(!spelled.phonetic);
+        assert!(spelled.score > explanation.score);
+
+        assert!(explain(&config, analysis, &search, "Jonathan Smart").is_none());
+    }
+
+    #[test]
+    fn explain_stop_words_earn_no_boundary_or_acronym_bonus() {
+        let normalizer = Normalizer::default();
+        let analysis = Analysis::new(&normalizer);
+        let config = ScoreConfiguration::builder()
+            .with_stop_words(["the".to_string()].into())
+            .build();
+        let phrase = "the science test";
+
+        let the = explain(&config, analysis, &Search::new("the"), phrase).unwrap();
+        let science = explain(&config, analysis, &Search::new("science"), phrase).unwrap();
+        assert_eq!(the.total(ScoreComponent::WordBoundary), 0);
+        assert_eq!(science.total(ScoreComponent::WordBoundary), 5);
+
+        let search = Search::builder().search("ts").acronym(true).build();
+        assert!(explain(&config, analysis, &search, phrase).is_none());
+        assert!(explain(&ScoreConfiguration::default(), analysis, &search, phrase).is_some());
+    }
+}
@@ -1,4 +1,4 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
 static DELIMITERS: &str = " -/_";
 
@@ -14,16 +14,28 @@ pub struct ScoreConfigurationBuilder {
     word_suffix_bonus: Option<u8>,
     character_offset_penalty: Option<u8>,
     max_offset_penalty: Option<u8>,
+    camel_case_boundaries: Option<bool>,
+    digit_boundaries: Option<bool>,
+    acronym_boundaries: Option<bool>,
+    acronym_bonus: Option<u8>,
+    script_boundaries: Option<bool>,
+    ideograph_boundaries: Option<bool>,
+    phonetic_penalty: Option<u8>,
+    stop_words: Option<HashSet<String>>,
+    frecency_weight: Option<u8>,
+    selection_bonus: Option<u8>,
+    field_weights: Option<HashMap<String, u8>>,
 }
 
 impl ScoreConfigurationBuilder {
+    /// Creates a builder which builds the default configuration
     pub fn new() -> Self {
         Self {
             ..Default::default()
         }
     }
 
-    /// Set delimiters used to mark word boundaries in the search scoring. 
+    /// Set delimiters used to mark word boundaries in the search scoring.
     pub fn with_word_delimiters(mut self, word_delimiters: HashSet<char>) -> Self {
         self.word_delimiters = Some(word_delimiters);
         self
@@ -45,36 +57,122 @@ impl ScoreConfigurationBuilder {
         self
     }
 
+    /// Caps the bonus of each adjacent character
     pub fn with_max_character_adjacency_bonus(mut self, max_character_adjacency_bonus: u8) -> Self {
         self.max_character_adjacency_bonus = Some(max_character_adjacency_bonus);
         self
     }
 
+    /// Sets the bonus for each run of the match starting a word
     pub fn with_word_boundary_bonus(mut self, word_boundary_bonus: u8) -> Self {
         self.word_boundary_bonus = Some(word_boundary_bonus);
         self
     }
 
+    /// Sets the bonus for a match starting at the beginning of the phrase
     pub fn with_word_prefix_bonus(mut self, word_prefix_bonus: u8) -> Self {
         self.word_prefix_bonus = Some(word_prefix_bonus);
         self
     }
 
+    /// Sets the bonus for a match ending at the end of a word
     pub fn with_word_suffix_bonus(mut self, word_suffix_bonus: u8) -> Self {
         self.word_suffix_bonus = Some(word_suffix_bonus);
         self
     }
 
+    /// Sets the penalty for each character skipped before the match
     pub fn with_character_offset_penalty(mut self, character_offset_penalty: u8) -> Self {
         self.character_offset_penalty = Some(character_offset_penalty);
         self
     }
 
+    /// Caps the penalty for the characters skipped before the match
     pub fn with_max_offset_penalty(mut self, max_offset_penalty: u8) -> Self {
         self.max_offset_penalty = Some(max_offset_penalty);
         self
     }
 
+    /// Treat a lowercase character followed by an uppercase character as a word boundary,
+    /// `parseHttp` is split into `parse|Http`
+    pub fn with_camel_case_boundaries(mut self, camel_case_boundaries: bool) -> Self {
+        self.camel_case_boundaries = Some(camel_case_boundaries);
+        self
+    }
+
+    /// Treat a transition between letters and digits as a word boundary,
+    /// `Request2Json` is split into `Request|2|Json`
+    pub fn with_digit_boundaries(mut self, digit_boundaries: bool) -> Self {
+        self.digit_boundaries = Some(digit_boundaries);
+        self
+    }
+
+    /// Treat the end of an uppercase acronym as a word boundary,
+    /// `HTTPServer` is split into `HTTP|Server`
+    pub fn with_acronym_boundaries(mut self, acronym_boundaries: bool) -> Self {
+        self.acronym_boundaries = Some(acronym_boundaries);
+        self
+    }
+
+    /// Sets the bonus for a search matching the initials of a phrase in acronym mode
+    pub fn with_acronym_bonus(mut self, acronym_bonus: u8) -> Self {
+        self.acronym_bonus = Some(acronym_bonus);
+        self
+    }
+
+    /// Treat a change of script between Han, Hiragana, Katakana, Hangul and other characters as a
+    /// word boundary, `東京タワー` is split into `東京|タワー`
+    pub fn with_script_boundaries(mut self, script_boundaries: bool) -> Self {
+        self.script_boundaries = Some(script_boundaries);
+        self
+    }
+
+    /// Treat every Han ideograph as a word of its own, `北京大学` is split into `北|京|大|学`.
+    /// Useful for Chinese, which is written without spaces between words.
+    pub fn with_ideograph_boundaries(mut self, ideograph_boundaries: bool) -> Self {
+        self.ideograph_boundaries = Some(ideograph_boundaries);
+        self
+    }
+
+    /// Sets the penalty for every search word which only sounds like a word of the phrase,
+    /// ordering phonetic matches among themselves. Phonetic matches rank below every spelled
+    /// match whatever the penalty.
+    pub fn with_phonetic_penalty(mut self, phonetic_penalty: u8) -> Self {
+        self.phonetic_penalty = Some(phonetic_penalty);
+        self
+    }
+
+    /// Set words, such as "the" and "of", which earn no word boundary or acronym bonus.
+    /// Stop words are compared case insensitively.
+    pub fn with_stop_words(mut self, stop_words: HashSet<String>) -> Self {
+        self.stop_words = Some(stop_words);
+        self
+    }
+
+    /// Sets how much the [Frecency](crate::frecency::Frecency) of a phrase adds to its score.
+    /// The bonus grows with the logarithm of the frecency.
+    pub fn with_frecency_weight(mut self, frecency_weight: u8) -> Self {
+        self.frecency_weight = Some(frecency_weight);
+        self
+    }
+
+    /// Sets the bonus for the phrase last selected for the same query, see
+    /// [SelectionMemory](crate::selection::SelectionMemory)
+    pub fn with_selection_bonus(mut self, selection_bonus: u8) -> Self {
+        self.selection_bonus = Some(selection_bonus);
+        self
+    }
+
+    /// Sets the weight of each named field of a record, see
+    /// [TrieNode::insert_record](crate::TrieNode::insert_record). The score of a record is
+    /// the sum of the scores of its matching fields, each multiplied by the field's weight.
+    /// Fields without a weight weigh 1.
+    pub fn with_field_weights(mut self, field_weights: HashMap<String, u8>) -> Self {
+        self.field_weights = Some(field_weights);
+        self
+    }
+
+    /// Builds the configuration, using the defaults for every value not set
     pub fn build(self) -> ScoreConfiguration {
         ScoreConfiguration {
             word_delimiters: self
@@ -88,6 +186,22 @@ impl ScoreConfigurationBuilder {
             word_suffix_bonus: self.word_suffix_bonus.unwrap_or(3),
             character_offset_penalty: self.character_offset_penalty.unwrap_or(1),
             max_offset_penalty: self.max_offset_penalty.unwrap_or(3),
+            camel_case_boundaries: self.camel_case_boundaries.unwrap_or(true),
+            digit_boundaries: self.digit_boundaries.unwrap_or(true),
+            acronym_boundaries: self.acronym_boundaries.unwrap_or(true),
+            acronym_bonus: self.acronym_bonus.unwrap_or(6),
+            script_boundaries: self.script_boundaries.unwrap_or(true),
+            ideograph_boundaries: self.ideograph_boundaries.unwrap_or(false),
+            phonetic_penalty: self.phonetic_penalty.unwrap_or(20),
+            stop_words: self
+                .stop_words
+                .unwrap_or_default()
+                .iter()
+                .map(|word| word.to_lowercase())
+                .collect(),
+            frecency_weight: self.frecency_weight.unwrap_or(5),
+            selection_bonus: self.selection_bonus.unwrap_or(100),
+            field_weights: self.field_weights.unwrap_or_default(),
         }
     }
 }
@@ -96,31 +210,64 @@ impl ScoreConfigurationBuilder {
 #[derive(Debug)]
 pub struct ScoreConfiguration {
     /// Characters which mark word boundaries
-    word_delimiters: HashSet<char>,
+    pub(crate) word_delimiters: HashSet<char>,
 
     /// bonus given to adjacent characters
-    character_adjacency_bonus: u8,
+    pub(crate) character_adjacency_bonus: u8,
 
     /// multiplier given to subsquent adjacent matching
-    character_adjacency_multiplier: u8,
+    pub(crate) character_adjacency_multiplier: u8,
 
     /// highest possible score given to a substring match
-    max_character_adjacency_bonus: u8,
+    pub(crate) max_character_adjacency_bonus: u8,
 
     /// bonus for the match being at the beginning of a word
-    word_boundary_bonus: u8,
+    pub(crate) word_boundary_bonus: u8,
 
     /// additional bonus if the match is a prefix
-    word_prefix_bonus: u8,
+    pub(crate) word_prefix_bonus: u8,
 
     /// additional bonus if the match is a word suffix
-    word_suffix_bonus: u8,
+    pub(crate) word_suffix_bonus: u8,
 
     /// penalty if the match does not start at the begining
-    character_offset_penalty: u8,
+    pub(crate) character_offset_penalty: u8,
 
     /// max penalty for the input not matching the start of the phrase
-    max_offset_penalty: u8,
+    pub(crate) max_offset_penalty: u8,
+
+    /// lower to upper case transitions mark word boundaries
+    pub(crate) camel_case_boundaries: bool,
+
+    /// letter to digit transitions mark word boundaries
+    pub(crate) digit_boundaries: bool,
+
+    /// the end of an upper case acronym marks a word boundary
+    pub(crate) acronym_boundaries: bool,
+
+    /// bonus for matching the word initials of a phrase in acronym mode
+    pub(crate) acronym_bonus: u8,
+
+    /// changes between CJK scripts mark word boundaries
+    pub(crate) script_boundaries: bool,
+
+    /// every Han ideograph is a word of its own
+    pub(crate) ideograph_boundaries: bool,
+
+    /// penalty for every search word matched by sound rather than spelling
+    pub(crate) phonetic_penalty: u8,
+
+    /// lowercased words which earn no word boundary or acronym bonus
+    pub(crate) stop_words: HashSet<String>,
+
+    /// weight of the logarithm of a phrase's frecency in its score
+    pub(crate) frecency_weight: u8,
+
+    /// bonus for the phrase last selected for the same query
+    pub(crate) selection_bonus: u8,
+
+    /// multiplier of the score of each named record field, 1 when missing
+    pub(crate) field_weights: HashMap<String, u8>,
 }
 
 impl ScoreConfiguration {
@@ -168,4 +315,16 @@ mod tests {
 
         assert_eq!(config.character_adjacency_bonus, CHARACTER_ADJACENCY_BONUS);
     }
+
+    #[test]
+    fn builder_with_stop_words_lowercases_them() {
+        let config = ScoreConfiguration::builder()
+            .with_stop_words(["The".to_string(), "of".to_string()].into())
+            .build();
+
+        assert_eq!(
+            config.stop_words,
+            ["the".to_string(), "of".to_string()].into()
+        );
+    }
 }
@@ -30,6 +30,11 @@
 //! The flow chart shows the state transitions of the `SearchBuilder` from `NoSearch` to `WithSearch` and finally to the `Search` struct when `build` is called.
 
 use std::fmt::Debug;
+use std::sync::Arc;
+use std::time::Instant;
+
+use crate::cancel::CancellationToken;
+use crate::filter::Filter;
 
 /// A TypeState builder for [Search].
 #[derive(Debug, Default)]
@@ -37,6 +42,13 @@ pub struct SearchBuilder<S: Debug + SearchMarker> {
     search: S,
     strict_case: Option<bool>,
     fuzzy: Option<bool>,
+    word_prefix: Option<bool>,
+    acronym: Option<bool>,
+    extended: Option<bool>,
+    phonetic: Option<bool>,
+    filter: Option<Arc<Filter>>,
+    cancellation: Option<CancellationToken>,
+    deadline: Option<Instant>,
 }
 
 /// A marker trait to ensure the builder state.
@@ -65,12 +77,28 @@ impl<S: Debug + SearchMarker> SearchBuilder<S> {
     /// ```
     pub fn search(self, search: &str) -> SearchBuilder<WithSearch<'_>> {
         let Self {
-            strict_case, fuzzy, ..
+            strict_case,
+            fuzzy,
+            word_prefix,
+            acronym,
+            extended,
+            phonetic,
+            filter,
+            cancellation,
+            deadline,
+            ..
         } = self;
         SearchBuilder {
             search: WithSearch(search),
             strict_case,
             fuzzy,
+            word_prefix,
+            acronym,
+            extended,
+            phonetic,
+            filter,
+            cancellation,
+            deadline,
         }
     }
 
@@ -101,6 +129,110 @@ impl<S: Debug + SearchMarker> SearchBuilder<S> {
         self.fuzzy = Some(fuzzy);
         self
     }
+
+    /// Restricts matching to word prefixes, every run of matched characters must begin a word.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use you_autocomplete_me::search::Search;
+    /// let search = Search::builder().search("hw").word_prefix(true).build();
+    /// assert!(search.word_prefix);
+    /// ```
+    pub fn word_prefix(mut self, word_prefix: bool) -> Self {
+        self.word_prefix = Some(word_prefix);
+        self
+    }
+
+    /// Matches every character of the search against the initials of the words in a phrase.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use you_autocomplete_me::search::Search;
+    /// let search = Search::builder().search("gcs").acronym(true).build();
+    /// assert!(search.acronym);
+    /// ```
+    pub fn acronym(mut self, acronym: bool) -> Self {
+        self.acronym = Some(acronym);
+        self
+    }
+
+    /// Parses the search value as an extended [Query](crate::query::Query).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use you_autocomplete_me::search::Search;
+    /// let search = Search::builder().search("^core go$ | rb$").extended(true).build();
+    /// assert!(search.extended);
+    /// ```
+    pub fn extended(mut self, extended: bool) -> Self {
+        self.extended = Some(extended);
+        self
+    }
+
+    /// Also matches words which sound like the words of the search, ranked below spelled matches.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use you_autocomplete_me::search::Search;
+    /// let search = Search::builder().search("jon smyth").phonetic(true).build();
+    /// assert!(search.phonetic);
+    /// ```
+    pub fn phonetic(mut self, phonetic: bool) -> Self {
+        self.phonetic = Some(phonetic);
+        self
+    }
+
+    /// Only matches phrases whose metadata matches the [Filter].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use you_autocomplete_me::filter::Filter;
+    /// use you_autocomplete_me::search::Search;
+    /// let search = Search::builder().search("read").filter(Filter::eq("lang", "en")).build();
+    /// assert_eq!(search.filter.as_deref(), Some(&Filter::eq("lang", "en")));
+    /// ```
+    pub fn filter(mut self, filter: Filter) -> Self {
+        self.filter = Some(Arc::new(filter));
+        self
+    }
+
+    /// Stops the search once the [CancellationToken] is cancelled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use you_autocomplete_me::cancel::CancellationToken;
+    /// use you_autocomplete_me::search::Search;
+    /// let token = CancellationToken::new();
+    /// let search = Search::builder().search("example").cancellation(token.clone()).build();
+    /// token.cancel();
+    /// assert!(search.cancellation.unwrap().is_cancelled());
+    /// ```
+    pub fn cancellation(mut self, cancellation: CancellationToken) -> Self {
+        self.cancellation = Some(cancellation);
+        self
+    }
+
+    /// Stops the search once the deadline passes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::{Duration, Instant};
+    /// use you_autocomplete_me::search::Search;
+    /// let deadline = Instant::now() + Duration::from_millis(5);
+    /// let search = Search::builder().search("example").deadline(deadline).build();
+    /// assert_eq!(search.deadline, Some(deadline));
+    /// ```
+    pub fn deadline(mut self, deadline: Instant) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
 }
 
 impl<'a> SearchBuilder<WithSearch<'a>> {
@@ -121,6 +253,13 @@ impl<'a> SearchBuilder<WithSearch<'a>> {
             search: self.search.0,
             strict_case: self.strict_case.unwrap_or(false),
             fuzzy: self.fuzzy.unwrap_or(false),
+            word_prefix: self.word_prefix.unwrap_or(false),
+            acronym: self.acronym.unwrap_or(false),
+            extended: self.extended.unwrap_or(false),
+            phonetic: self.phonetic.unwrap_or(false),
+            filter: self.filter,
+            cancellation: self.cancellation,
+            deadline: self.deadline,
         }
     }
 }
@@ -140,7 +279,7 @@ impl SearchBuilder<NoSearch> {
 }
 
 /// Represents the search query.
-#[derive(Debug, Default)]
+#[derive(Debug, Clone, Default)]
 pub struct Search<'a> {
     /// The search query string.
     pub search: &'a str,
@@ -148,6 +287,20 @@ pub struct Search<'a> {
     pub strict_case: bool,
     /// Allow fuzzy search.
     pub fuzzy: bool,
+    /// Only match at word prefixes.
+    pub word_prefix: bool,
+    /// Match against word initials.
+    pub acronym: bool,
+    /// Use the extended query syntax.
+    pub extended: bool,
+    /// Match words which sound alike.
+    pub phonetic: bool,
+    /// Only match phrases whose metadata matches, shared so that copies of the search are cheap.
+    pub filter: Option<Arc<Filter>>,
+    /// Stop searching once cancelled.
+    pub cancellation: Option<CancellationToken>,
+    /// Stop searching once passed.
+    pub deadline: Option<Instant>,
 }
 
 impl<'a> Search<'a> {
@@ -206,6 +359,12 @@ impl<'a> Search<'a> {
     }
 }
 
+impl<'a> From<&'a str> for Search<'a> {
+    fn from(search: &'a str) -> Self {
+        Self::new(search)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
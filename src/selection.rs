@@ -0,0 +1,136 @@
+//! # Selection Module
+//!
+//! Remembers which phrase was selected for a query, so that searching for the same query
+//! again ranks it first. Unlike [Frecency](crate::frecency::Frecency), which learns the
+//! overall popularity of phrases, the memory is conditioned on what was typed. It holds a
+//! bounded number of queries, forgetting the least recently used first.
+//!
+//! ## Example
+//!
+//! ```
+//! use you_autocomplete_me::selection::SelectionMemory;
+//! use you_autocomplete_me::{Completer, TrieNode, YouAutoCompleteMe};
+//! let mut phrase_book = TrieNode::default();
+//! phrase_book.insert("production-east");
+//! phrase_book.insert("production-west");
+//! let memory = SelectionMemory::new(1_000);
+//! let completer = YouAutoCompleteMe::builder(&phrase_book)
+//!     .with_selection_memory(&memory)
+//!     .build();
+//! assert_eq!(completer.complete("pr")[0].phrase, "production-east");
+//!
+//! completer.record_selection("pr", "production-west");
+//! assert_eq!(completer.complete("pr")[0].phrase, "production-west");
+//! assert_eq!(completer.complete("pro")[0].phrase, "production-east");
+//! ```
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+
+/// The phrase last selected for each query, up to a capacity of queries. Selections are
+/// recorded through a shared reference, like [Frecency](crate::frecency::Frecency).
+#[derive(Debug)]
+pub struct SelectionMemory {
+    capacity: usize,
+    lru: Mutex<Lru>,
+}
+
+#[derive(Debug, Default)]
+struct Lru {
+    /// The selected phrase and the last use of every query
+    selections: HashMap<String, (String, u64)>,
+    /// Queries by their last use, least recent first
+    uses: BTreeMap<u64, String>,
+    clock: u64,
+}
+
+impl SelectionMemory {
+    /// Creates an empty memory remembering up to `capacity` queries
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            lru: Mutex::new(Lru::default()),
+        }
+    }
+
+    /// Remembers the phrase as the selection for the query, forgetting the least recently
+    /// used query when the memory is full. Queries are compared case insensitively.
+    pub fn record(&self, query: &str, phrase: &str) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut lru = self.lru.lock().unwrap();
+        let query = key(query);
+        let tick = lru.touch(&query);
+        lru.selections.insert(query, (phrase.to_string(), tick));
+        while lru.selections.len() > self.capacity {
+            let Some((_, oldest)) = lru.uses.pop_first() else {
+                break;
+            };
+            lru.selections.remove(&oldest);
+        }
+    }
+
+    /// The phrase last selected for the query, which counts as using it
+    pub fn selection(&self, query: &str) -> Option<String> {
+        let mut lru = self.lru.lock().unwrap();
+        let query = key(query);
+        if !lru.selections.contains_key(&query) {
+            return None;
+        }
+        let tick = lru.touch(&query);
+        let (phrase, used) = lru.selections.get_mut(&query)?;
+        *used = tick;
+        Some(phrase.clone())
+    }
+
+    /// The number of remembered queries
+    pub fn len(&self) -> usize {
+        self.lru.lock().unwrap().selections.len()
+    }
+
+    /// Whether no query is remembered
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Lru {
+    /// Moves the query to the most recently used, returning its new use
+    fn touch(&mut self, query: &str) -> u64 {
+        if let Some((_, used)) = self.selections.get(query) {
+            self.uses.remove(used);
+        }
+        self.clock += 1;
+        self.uses.insert(self.clock, query.to_string());
+        self.clock
+    }
+}
+
+fn key(query: &str) -> String {
+    query.trim().to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn least_recently_used_queries_are_forgotten() {
+        let memory = SelectionMemory::new(2);
+        memory.record("a", "apple");
+        memory.record("b", "banana");
+        assert_eq!(memory.selection("A ").as_deref(), Some("apple"));
+
+        memory.record("c", "cherry");
+        assert_eq!(memory.len(), 2);
+        assert_eq!(memory.selection("b"), None);
+        assert_eq!(memory.selection("a").as_deref(), Some("apple"));
+
+        memory.record("c", "clementine");
+        memory.record("d", "date");
+        assert_eq!(memory.selection("a"), None);
+        assert_eq!(memory.selection("c").as_deref(), Some("clementine"));
+        assert_eq!(memory.selection("d").as_deref(), Some("date"));
+    }
+}
@@ -0,0 +1,147 @@
+//! # Session Module
+//!
+//! Searches the phrase book as the user types. A [SearchSession] remembers the trie node
+//! reached by every character of the search and the phrases found below it. Typing a
+//! character descends a single node from the previous one, and deleting a character returns
+//! to phrases already found. The phrases below a new node are collected from its subtree when
+//! first asked for, which shrinks as the search grows.
+//!
+//! ## Example
+//!
+//! ```
+//! use you_autocomplete_me::TrieNode;
+//! let mut phrase_book = TrieNode::default();
+//! for phrase in ["hello", "help", "world"] {
+//!     phrase_book.insert(phrase);
+//! }
+//! let mut session = phrase_book.session();
+//!
+//! session.push('h');
+//! session.push('e');
+//! session.push('l');
+//! assert_eq!(session.candidates().len(), 2);
+//! session.push('p');
+//! assert_eq!(session.candidates().len(), 1);
+//! session.pop();
+//! assert_eq!(session.candidates().len(), 2);
+//!
+//! session.set_input("wor");
+//! assert!(session.candidates().contains("world"));
+//! ```
+
+use std::collections::HashSet;
+
+use crate::cancel::Interrupt;
+use crate::trie::{Node, TrieNode};
+
+/// An incremental search of a phrase book, see [session](crate::session)
+#[derive(Debug)]
+pub struct SearchSession<'a, P = ()> {
+    phrase_book: &'a TrieNode<P>,
+    input: String,
+    /// The analyzed search
+    key: Vec<char>,
+    /// The node reached by every prefix of the key, ending early when the trie does
+    nodes: Vec<&'a Node>,
+    /// The phrases below each of the nodes, once collected
+    candidates: Vec<Option<HashSet<&'a str>>>,
+    empty: HashSet<&'a str>,
+}
+
+impl<'a, P> SearchSession<'a, P> {
+    pub(crate) fn new(phrase_book: &'a TrieNode<P>) -> Self {
+        Self {
+            phrase_book,
+            input: String::new(),
+            key: Vec::new(),
+            nodes: vec![phrase_book.root()],
+            candidates: vec![None],
+            empty: HashSet::new(),
+        }
+    }
+
+    /// The search typed so far
+    pub fn input(&self) -> &str {
+        &self.input
+    }
+
+    /// Appends a character to the search
+    pub fn push(&mut self, c: char) {
+        let mut input = std::mem::take(&mut self.input);
+        input.push(c);
+        self.set_input(&input);
+    }
+
+    /// Removes the last character of the search, returning it
+    pub fn pop(&mut self) -> Option<char> {
+        let mut input = std::mem::take(&mut self.input);
+        let popped = input.pop();
+        self.set_input(&input);
+        popped
+    }
+
+    /// Replaces the search, keeping the nodes of the prefix it shares with the previous one
+    pub fn set_input(&mut self, input: &str) {
+        let key = self.phrase_book.search_key(input);
+        let shared = self
+            .key
+            .iter()
+            .zip(&key)
+            .take_while(|(a, b)| a == b)
+            .count();
+        let depth = shared.min(self.nodes.len() - 1);
+        self.nodes.truncate(depth + 1);
+        self.candidates.truncate(depth + 1);
+
+        for c in &key[depth..] {
+            let Some(child) = self.nodes[self.nodes.len() - 1].child(*c) else {
+                break;
+            };
+            self.nodes.push(child);
+            self.candidates.push(None);
+        }
+        self.key = key;
+        self.input = input.to_string();
+    }
+
+    /// The phrases matching the search, as [TrieNode::search] would find them. Collects the
+    /// subtree of the deepest node the first time it is asked for at that depth.
+    pub fn candidates(&mut self) -> &HashSet<&'a str> {
+        if self.nodes.len() <= self.key.len() {
+            return &self.empty;
+        }
+        let depth = self.nodes.len() - 1;
+        let node = self.nodes[depth];
+        self.candidates[depth].get_or_insert_with(|| {
+            node.collect(&|_| true, &Interrupt::never())
+                .into_iter()
+                .collect()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn session_finds_what_search_finds() {
+        let mut phrase_book = TrieNode::default();
+        for phrase in ["hello-world", "World", "help", "helium", "Hello"] {
+            phrase_book.insert(phrase);
+        }
+        let mut session = phrase_book.session();
+
+        for input in [
+            "h", "he", "hel", "helx", "hel", "He", "Hel", "wor", "", "lium",
+        ] {
+            session.set_input(input);
+            assert_eq!(session.candidates(), &phrase_book.search(input), "{input}");
+        }
+
+        session.set_input("help");
+        assert_eq!(session.pop(), Some('p'));
+        assert_eq!(session.input(), "hel");
+        assert_eq!(session.candidates(), &phrase_book.search("hel"));
+    }
+}
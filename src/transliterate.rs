@@ -0,0 +1,261 @@
+//! # Transliterate Module
+//!
+//! Transliterators are [Analyzer]s which write a phrase in another script, so that latin input
+//! can complete phrases written in Japanese or Chinese. The phrase book indexes every
+//! transliteration of a phrase next to its analyzed text, and matches against either of them.
+//!
+//! - [Romaji]: writes hiragana and katakana in Hepburn romaji, "タワー" becomes "tawaa"
+//! - [Readings]: replaces words with readings from a table, such as pinyin for hanzi or the
+//!   readings of kanji, which cannot be derived without a dictionary
+//!
+//! ## Example
+//!
+//! ```
+//! use you_autocomplete_me::analyzer::Chain;
+//! use you_autocomplete_me::transliterate::{Readings, Romaji};
+//! use you_autocomplete_me::TrieNode;
+//! let mut phrase_book = TrieNode::default()
+//!     .with_transliterator(Chain::new()
+//!         .with(Readings::new().with("東京", "toukyou"))
+//!         .with(Romaji))
+//!     .with_transliterator(Readings::new().with("北京", "bei jing"));
+//! phrase_book.insert("東京タワー");
+//! phrase_book.insert("北京");
+//!
+//! assert!(phrase_book.search("toukyoutawa").contains("東京タワー"));
+//! assert!(phrase_book.search("tawa").contains("東京タワー"));
+//! assert!(phrase_book.search("beijing").contains("北京"));
+//! ```
+
+use std::collections::HashMap;
+
+use crate::analyzer::{AnalyzedText, Analyzer};
+
+/// Hepburn romaji of the hiragana from `ぁ` to `ゖ`, `っ` is handled by doubling the consonant
+/// which follows it
+static HIRAGANA: [&str; 86] = [
+    "a", "a", "i", "i", "u", "u", "e", "e", "o", "o", // ぁ - お
+    "ka", "ga", "ki", "gi", "ku", "gu", "ke", "ge", "ko", "go", // か - ご
+    "sa", "za", "shi", "ji", "su", "zu", "se", "ze", "so", "zo", // さ - ぞ
+    "ta", "da", "chi", "ji", "", "tsu", "zu", "te", "de", "to", "do", // た - ど
+    "na", "ni", "nu", "ne", "no", // な - の
+    "ha", "ba", "pa", "hi", "bi", "pi", "fu", "bu", "pu", "he", "be", "pe", "ho", "bo",
+    "po", // は - ぽ
+    "ma", "mi", "mu", "me", "mo", // ま - も
+    "ya", "ya", "yu", "yu", "yo", "yo", // ゃ - よ
+    "ra", "ri", "ru", "re", "ro", // ら - ろ
+    "wa", "wa", "i", "e", "o", "n", "vu", "ka", "ke", // ゎ - ゖ
+];
+
+/// Writes hiragana and katakana in Hepburn romaji, leaving every other character as is
+/// ```
+/// use you_autocomplete_me::analyzer::{AnalyzedText, Analyzer};
+/// use you_autocomplete_me::transliterate::Romaji;
+/// let text = Romaji.analyze(AnalyzedText::new("らーめん と きっぷ ジャケット"));
+/// assert_eq!(text.chars().iter().collect::<String>(), "raamen to kippu jaketto");
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Romaji;
+
+impl Romaji {
+    fn syllable(c: char) -> Option<&'static str> {
+        let hiragana = match c {
+            '\u{30A1}'..='\u{30F6}' => char::from_u32(u32::from(c) - 0x60)?,
+            _ => c,
+        };
+        match hiragana {
+            '\u{3041}'..='\u{3096}' => Some(HIRAGANA[(u32::from(hiragana) - 0x3041) as usize]),
+            _ => None,
+        }
+    }
+
+    /// The vowel of a small `ゃ`, `ゅ` or `ょ`, which combine with the kana before them
+    fn small_y(c: char) -> Option<char> {
+        match c {
+            'ゃ' | 'ャ' => Some('a'),
+            'ゅ' | 'ュ' => Some('u'),
+            'ょ' | 'ョ' => Some('o'),
+            _ => None,
+        }
+    }
+}
+
+impl Analyzer for Romaji {
+    fn analyze(&self, text: AnalyzedText) -> AnalyzedText {
+        let mut romaji = AnalyzedText::default();
+        // origin of a small tsu waiting to double the next consonant
+        let mut geminate = None;
+        let mut i = 0;
+        while i < text.chars.len() {
+            let (c, origin) = (text.chars[i], text.origins[i]);
+            i += 1;
+
+            if matches!(c, 'っ' | 'ッ') {
+                geminate = Some(origin);
+                continue;
+            }
+            let Some(syllable) = Self::syllable(c) else {
+                match romaji.chars.last() {
+                    Some(&vowel) if c == 'ー' && "aeiou".contains(vowel) => {
+                        romaji.push(vowel, origin)
+                    }
+                    _ => romaji.push(c, origin),
+                }
+                geminate = None;
+                continue;
+            };
+
+            // きゃ is written kya, しゃ sha, ちゃ cha and じゃ ja
+            let digraph = text
+                .chars
+                .get(i)
+                .and_then(|&next| Self::small_y(next))
+                .filter(|_| syllable.len() > 1 && syllable.ends_with('i'));
+            let head = match digraph {
+                Some(_) => &syllable[..syllable.len() - 1],
+                None => syllable,
+            };
+
+            if let Some(geminate) = geminate.take() {
+                match head.chars().next() {
+                    Some('c') => romaji.push('t', geminate),
+                    Some(consonant) if !"aeiou".contains(consonant) => {
+                        romaji.push(consonant, geminate)
+                    }
+                    _ => {}
+                }
+            }
+            head.chars().for_each(|c| romaji.push(c, origin));
+            if let Some(vowel) = digraph {
+                if !matches!(head, "sh" | "ch" | "j") {
+                    romaji.push('y', text.origins[i]);
+                }
+                romaji.push(vowel, text.origins[i]);
+                i += 1;
+            }
+        }
+        romaji
+    }
+}
+
+/// Replaces words with their readings, preferring the longest word at every position.
+///
+/// When the reading has one space separated part per character of the word, each part is
+/// matched back to its own character, otherwise the whole reading belongs to the first one.
+/// ```
+/// use you_autocomplete_me::analyzer::{AnalyzedText, Analyzer};
+/// use you_autocomplete_me::transliterate::Readings;
+/// let readings = Readings::new()
+///     .with("北京", "bei jing")
+///     .with("大学", "daxue")
+///     .with("北", "bei");
+/// let text = readings.analyze(AnalyzedText::new("北京大学"));
+/// assert_eq!(text.chars().iter().collect::<String>(), "beijingdaxue");
+/// assert_eq!(text.origins(), &[0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 2]);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Readings {
+    readings: HashMap<Vec<char>, Vec<Vec<char>>>,
+    longest: usize,
+}
+
+impl Readings {
+    /// Creates a table without any readings
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Adds the reading of a word, replacing any previous reading
+    pub fn with(mut self, word: &str, reading: &str) -> Self {
+        let word: Vec<char> = word.chars().collect();
+        if word.is_empty() {
+            return self;
+        }
+        let parts: Vec<Vec<char>> = reading
+            .split_whitespace()
+            .map(|part| part.chars().collect())
+            .collect();
+        let parts = match parts.len() == word.len() {
+            true => parts,
+            false => vec![parts.concat()],
+        };
+        self.longest = self.longest.max(word.len());
+        self.readings.insert(word, parts);
+        self
+    }
+}
+
+impl Analyzer for Readings {
+    fn analyze(&self, text: AnalyzedText) -> AnalyzedText {
+        let mut read = AnalyzedText::default();
+        let mut i = 0;
+        while i < text.chars.len() {
+            let longest = self.longest.min(text.chars.len() - i);
+            let reading = (1..=longest)
+                .rev()
+                .find_map(|len| Some((len, self.readings.get(&text.chars[i..i + len])?)));
+            match reading {
+                Some((len, parts)) => {
+                    for (part, origin) in parts.iter().zip(&text.origins[i..i + len]) {
+                        part.iter().for_each(|&c| read.push(c, *origin));
+                    }
+                    i += len;
+                }
+                None => {
+                    read.push(text.chars[i], text.origins[i]);
+                    i += 1;
+                }
+            }
+        }
+        read
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn romaji(text: &str) -> String {
+        Romaji.analyze(AnalyzedText::new(text)).key()
+    }
+
+    #[test]
+    fn romaji_writes_every_kana() {
+        assert_eq!(HIRAGANA.len(), ('ぁ'..='ゖ').count());
+        assert_eq!(romaji("すし"), "sushi");
+        assert_eq!(romaji("カタカナ"), "katakana");
+        assert_eq!(romaji("ふじさん"), "fujisan");
+    }
+
+    #[test]
+    fn romaji_combines_digraphs_and_doubles_consonants() {
+        assert_eq!(romaji("きょうと"), "kyouto");
+        assert_eq!(romaji("しゃしん"), "shashin");
+        assert_eq!(romaji("チョコ"), "choko");
+        assert_eq!(romaji("まっちゃ"), "matcha");
+        assert_eq!(romaji("ロケット"), "roketto");
+    }
+
+    #[test]
+    fn romaji_keeps_origins() {
+        let text = Romaji.analyze(AnalyzedText::new("東京タワー"));
+
+        assert_eq!(text.key(), "東京tawaa");
+        assert_eq!(text.origins(), &[0, 1, 2, 2, 3, 3, 4]);
+
+        let text = Romaji.analyze(AnalyzedText::new("まっちゃ"));
+        assert_eq!(text.origins(), &[0, 0, 1, 2, 2, 3]);
+    }
+
+    #[test]
+    fn readings_prefer_the_longest_word() {
+        let readings = Readings::new()
+            .with("東京", "toukyou")
+            .with("東", "higashi")
+            .with("京", "kyou");
+        let text = readings.analyze(AnalyzedText::new("東京と東"));
+
+        assert_eq!(text.key(), "toukyouとhigashi");
+        assert_eq!(text.origins()[..8], [0, 0, 0, 0, 0, 0, 0, 2]);
+    }
+}
@@ -1,51 +1,502 @@
 use itertools::Itertools;
 use std::collections::{HashMap, HashSet};
+use std::iter;
 use std::str::Chars;
 
-use crate::match_profile::MatchProfile;
-use crate::search::Search;
+use crate::analyzer::{Analysis, Analyzer};
+use crate::boundary::{self, grapheme_starts};
+use crate::cancel::Interrupt;
+use crate::filter::{Filter, Metadata, Value};
+use crate::normalize::Normalizer;
+use crate::phonetic;
+use crate::prediction::{NGrams, Prediction};
+use crate::session::SearchSession;
 
-/// Represents a data type which can be searched
-pub trait Searcher {
-    type T<'a>;
-
-    /// .
-    fn search<'a>(&self, s: Search<'a>) -> Self::T<'a>;
+/// The phrase book: a suffix trie of the inserted phrases and the payloads attached to them
+/// Todo: Generic so I can bench Rc<str>
+///
+#[derive(Debug)]
+pub struct TrieNode<P = ()> {
+    root: Node,
+    payloads: HashMap<String, P>,
+    analyzer: Box<dyn Analyzer>,
+    transliterators: Vec<Box<dyn Analyzer>>,
+    /// Phrases by the phonetic codes of their words, when enabled
+    phonetic: Option<HashMap<String, HashSet<String>>>,
+    /// Lowercased words which are not indexed as suffix starting points
+    stop_words: HashSet<String>,
+    /// N-grams of the phrases' words, when next word prediction is enabled
+    ngrams: Option<NGrams>,
+    /// The named fields of phrases inserted as records, indexed in place of the phrase
+    records: HashMap<String, Vec<(String, String)>>,
+    metadata: HashMap<String, Metadata>,
+    /// Explicit group keys of phrases naming the same entity
+    groups: HashMap<String, String>,
 }
 
-impl Searcher for TrieNode {
-    type T<'a> = Box<dyn Iterator<Item=MatchProfile<'a>>>;
+#[derive(Default, Debug)]
+pub(crate) struct Node {
+    children: HashMap<char, Node>,
+    word: HashSet<String>,
+}
 
-    fn search<'a>(&self, _s: Search<'a>) -> Self::T<'a> {
-        // _s.search
-        todo!()
+impl Default for TrieNode {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
-/// Todo: Generic so I can bench Rc<str>
-///
-#[derive(Default, Debug)]
-pub struct TrieNode {
-    children: HashMap<char, TrieNode>,
-    word: HashSet<String>,
-}
+impl<P> TrieNode<P> {
+    /// Creates an empty phrase book
+    /// ```
+    /// use you_autocomplete_me::TrieNode;
+    /// let mut phrase_book = TrieNode::new();
+    /// phrase_book.insert_with_payload("hello", 42);
+    /// assert_eq!(phrase_book.payload("hello"), Some(&42));
+    /// ```
+    pub fn new() -> Self {
+        Self {
+            root: Node::default(),
+            payloads: HashMap::new(),
+            analyzer: Box::new(Normalizer::default()),
+            transliterators: Vec::new(),
+            phonetic: None,
+            stop_words: HashSet::new(),
+            ngrams: None,
+            records: HashMap::new(),
+            metadata: HashMap::new(),
+            groups: HashMap::new(),
+        }
+    }
+
+    /// Sets the [Analyzer] applied to phrases and searches, re-indexing any inserted phrases.
+    /// Defaults to a [Normalizer] using NFC.
+    /// ```
+    /// use you_autocomplete_me::normalize::Normalizer;
+    /// use you_autocomplete_me::TrieNode;
+    /// let mut phrase_book =
+    ///     TrieNode::default().with_analyzer(Normalizer::new().with_accent_stripping(true));
+    /// phrase_book.insert("Café");
+    /// assert!(phrase_book.search("cafe").contains("Café"));
+    /// ```
+    pub fn with_analyzer(mut self, analyzer: impl Analyzer + 'static) -> Self {
+        self.analyzer = Box::new(analyzer);
+        self.reindex();
+        self
+    }
+
+    /// Adds a transliterator, see [transliterate](crate::transliterate), indexing every phrase
+    /// under its transliteration as well. Transliterators are applied after the analyzer.
+    /// ```
+    /// use you_autocomplete_me::transliterate::Romaji;
+    /// use you_autocomplete_me::TrieNode;
+    /// let mut phrase_book = TrieNode::default().with_transliterator(Romaji);
+    /// phrase_book.insert("ラーメン");
+    /// assert!(phrase_book.search("ramen").is_empty());
+    /// assert!(phrase_book.search("raamen").contains("ラーメン"));
+    /// ```
+    pub fn with_transliterator(mut self, transliterator: impl Analyzer + 'static) -> Self {
+        self.transliterators.push(Box::new(transliterator));
+        self.reindex();
+        self
+    }
+
+    /// Keeps an index of the Double Metaphone codes of every word, used by phonetic searches,
+    /// see [phonetic](crate::phonetic)
+    /// ```
+    /// use you_autocomplete_me::TrieNode;
+    /// let mut phrase_book = TrieNode::default().with_phonetic_index(true);
+    /// phrase_book.insert("Catherine Smith");
+    /// assert!(phrase_book.phonetic_search("kathryn smyth").contains("Catherine Smith"));
+    /// ```
+    pub fn with_phonetic_index(mut self, phonetic_index: bool) -> Self {
+        self.phonetic = phonetic_index.then(HashMap::new);
+        self.reindex();
+        self
+    }
+
+    /// Stops indexing suffixes starting within the stop words, and completing matches starting
+    /// there, so that searching for a stop word only finds phrases beginning with it. Words are
+    /// split like the default [ScoreConfiguration](crate::score_configuration::ScoreConfiguration),
+    /// usually with the same stop words.
+    /// ```
+    /// use you_autocomplete_me::TrieNode;
+    /// let mut phrase_book = TrieNode::default().with_stop_words(["the".to_string()].into());
+    /// phrase_book.insert("The science test");
+    /// phrase_book.insert("On the moon");
+    /// assert_eq!(phrase_book.search("the").len(), 1);
+    /// assert_eq!(phrase_book.search("moon").len(), 1);
+    /// ```
+    pub fn with_stop_words(mut self, stop_words: HashSet<String>) -> Self {
+        self.stop_words = stop_words.iter().map(|word| word.to_lowercase()).collect();
+        self.reindex();
+        self
+    }
+
+    /// Learns which words follow up to `order - 1` words in the phrases, used to predict the
+    /// next word, see [prediction](crate::prediction). An order below 2 turns it off.
+    /// ```
+    /// use you_autocomplete_me::TrieNode;
+    /// let mut phrase_book = TrieNode::default().with_next_word_prediction(2);
+    /// phrase_book.insert("hello world");
+    /// assert_eq!(phrase_book.predict("hello ")[0].word, "world");
+    /// assert!(TrieNode::default().predict("hello ").is_empty());
+    /// ```
+    pub fn with_next_word_prediction(mut self, order: usize) -> Self {
+        self.ngrams = (order >= 2).then(|| NGrams::new(order));
+        self.reindex();
+        self
+    }
+
+    /// The [Analyzer] applied to phrases and searches
+    pub fn analyzer(&self) -> &dyn Analyzer {
+        self.analyzer.as_ref()
+    }
+
+    pub(crate) fn analysis(&self) -> Analysis<'_> {
+        Analysis::new(self.analyzer())
+            .with_transliterators(&self.transliterators)
+            .with_stop_words(&self.stop_words)
+    }
+
+    fn reindex(&mut self) {
+        let phrases: Vec<String> = self.search("").into_iter().map(String::from).collect();
+        self.root = Node::default();
+        if let Some(phonetic) = &mut self.phonetic {
+            phonetic.clear();
+        }
+        if let Some(ngrams) = &mut self.ngrams {
+            ngrams.clear();
+        }
+        for phrase in &phrases {
+            self.index(phrase);
+        }
+    }
 
-impl TrieNode {
     /// Not documenting as this will not be part of the public api
     pub fn insert(&mut self, value: &str) {
+        self.index(value);
+    }
+
+    /// Inserts a phrase and attaches a payload to it, replacing any previous payload
+    pub fn insert_with_payload(&mut self, value: &str, payload: P) {
+        if value.is_empty() {
+            return;
+        }
+        self.index(value);
+        self.payloads.insert(value.to_string(), payload);
+    }
+
+    /// Inserts a phrase as a record with named fields, such as a title, tags and a
+    /// description, replacing the fields of any record inserted as the same phrase. Searches
+    /// match the fields rather than the phrase, see
+    /// [ScoreConfigurationBuilder::with_field_weights](crate::ScoreConfigurationBuilder::with_field_weights).
+    /// ```
+    /// use you_autocomplete_me::TrieNode;
+    /// let mut phrase_book = TrieNode::default();
+    /// phrase_book.insert_record("rust-book", &[("title", "The Rust Book"), ("tags", "learning")]);
+    /// assert!(phrase_book.search("learn").contains("rust-book"));
+    /// assert!(phrase_book.search("-book").is_empty());
+    /// ```
+    pub fn insert_record(&mut self, phrase: &str, fields: &[(&str, &str)]) {
+        if phrase.is_empty() {
+            return;
+        }
+        let fields = fields
+            .iter()
+            .filter(|(_, text)| !text.is_empty())
+            .map(|&(name, text)| (name.to_string(), text.to_string()))
+            .collect();
+        // the phrase was indexed by the fields of its previous record, or by itself when inserted
+        match self.records.insert(phrase.to_string(), fields) {
+            Some(previous) => {
+                for (_, text) in &previous {
+                    self.unindex_text(text, phrase);
+                }
+            }
+            None => self.unindex_text(phrase, phrase),
+        }
+        if let Some(ngrams) = &mut self.ngrams {
+            ngrams.forget(phrase);
+        }
+        self.index(phrase);
+    }
+
+    /// The named fields of a phrase inserted as a record
+    pub fn fields(&self, phrase: &str) -> Option<&[(String, String)]> {
+        self.records.get(phrase).map(Vec::as_slice)
+    }
+
+    /// Inserts a phrase and attaches metadata to it, replacing any previous metadata. Searches
+    /// may [filter](crate::filter) phrases by their metadata.
+    pub fn insert_with_metadata<K: Into<String>>(
+        &mut self,
+        value: &str,
+        metadata: impl IntoIterator<Item = (K, Value)>,
+    ) {
+        if value.is_empty() {
+            return;
+        }
+        self.index(value);
+        let metadata = metadata
+            .into_iter()
+            .map(|(attribute, value)| (attribute.into(), value))
+            .collect();
+        self.metadata.insert(value.to_string(), metadata);
+    }
+
+    /// The metadata attached to a phrase
+    pub fn metadata(&self, phrase: &str) -> Option<&Metadata> {
+        self.metadata.get(phrase)
+    }
+
+    /// Inserts a phrase as a variant of the group, such as an alias or alternative spelling of
+    /// the same entity, replacing any previous group. Grouping completers collapse the
+    /// variants into the best matching one, see
+    /// [with_grouping](crate::YouAutoCompleteMeBuilder::with_grouping).
+    pub fn insert_in_group(&mut self, value: &str, group: &str) {
+        if value.is_empty() {
+            return;
+        }
+        self.index(value);
+        self.groups.insert(value.to_string(), group.to_string());
+    }
+
+    /// The group key of a phrase: its explicit group, otherwise the phrase analyzed and case
+    /// folded, so that case variants share a group
+    pub fn group(&self, phrase: &str) -> String {
+        match self.groups.get(phrase) {
+            Some(group) => group.clone(),
+            None => self.analysis().search(phrase, true).key(),
+        }
+    }
+
+    /// The payload attached to a phrase
+    pub fn payload(&self, phrase: &str) -> Option<&P> {
+        self.payloads.get(phrase)
+    }
+
+    /// Not documenting due to changes to the api
+    pub fn search(&self, value: &str) -> HashSet<&str> {
+        self.filtered_search(value, None)
+    }
+
+    /// Phrases found by [search](TrieNode::search) whose metadata matches the filter, which is
+    /// applied while the trie is traversed
+    pub fn filtered_search(&self, value: &str, filter: Option<&Filter>) -> HashSet<&str> {
+        self.interruptible_search(value, filter, &Interrupt::never())
+    }
+
+    /// Phrases found by [filtered_search](TrieNode::filtered_search) before the interrupt fires
+    pub(crate) fn interruptible_search(
+        &self,
+        value: &str,
+        filter: Option<&Filter>,
+        interrupt: &Interrupt<'_>,
+    ) -> HashSet<&str> {
+        let key = self.search_key(value);
+        let keep = |phrase: &str| filter.is_none_or(|f| f.matches(self.metadata(phrase)));
+        self.root.search(key.into_iter(), &keep, interrupt)
+    }
+
+    /// Phrases containing the first character of the analyzed value, the candidates of
+    /// searches which may match anywhere in a phrase
+    pub(crate) fn first_character_search(
+        &self,
+        value: &str,
+        filter: Option<&Filter>,
+        interrupt: &Interrupt<'_>,
+    ) -> HashSet<&str> {
+        let key = self.search_key(value);
+        let keep = |phrase: &str| filter.is_none_or(|f| f.matches(self.metadata(phrase)));
+        self.root.search(key.into_iter().take(1), &keep, interrupt)
+    }
+
+    /// Starts an incremental search, see [session](crate::session)
+    pub fn session(&self) -> SearchSession<'_, P> {
+        SearchSession::new(self)
+    }
+
+    /// The key searched for the value, case folded unless it has uppercase characters
+    pub(crate) fn search_key(&self, value: &str) -> Vec<char> {
+        let fold = !value.chars().any(|c| c.is_uppercase());
+        self.analysis().search(value, fold).chars().to_vec()
+    }
+
+    pub(crate) fn records(&self) -> &HashMap<String, Vec<(String, String)>> {
+        &self.records
+    }
+
+    pub(crate) fn root(&self) -> &Node {
+        &self.root
+    }
+
+    /// Phrases with a word sounding like every word of the value,
+    /// empty unless the phonetic index is enabled
+    pub fn phonetic_search(&self, value: &str) -> HashSet<&str> {
+        let Some(index) = &self.phonetic else {
+            return HashSet::new();
+        };
+        value
+            .split_whitespace()
+            .map(|word| {
+                phonetic::codes(word)
+                    .iter()
+                    .filter_map(|code| index.get(code))
+                    .flatten()
+                    .map(String::as_str)
+                    .collect::<HashSet<_>>()
+            })
+            .reduce(|a, b| a.intersection(&b).copied().collect())
+            .unwrap_or_default()
+    }
+
+    /// Words predicted to follow the text, best first, empty unless next word prediction is
+    /// enabled
+    pub fn predict(&self, text: &str) -> Vec<Prediction<'_>> {
+        self.ngrams
+            .as_ref()
+            .map(|ngrams| ngrams.predict(text))
+            .unwrap_or_default()
+    }
+
+    /// Indexes the phrase, or the fields of its record under the phrase
+    fn index(&mut self, phrase: &str) {
+        match self.records.get(phrase) {
+            Some(fields) => {
+                let texts: Vec<String> = fields.iter().map(|(_, text)| text.clone()).collect();
+                for text in &texts {
+                    self.index_text(text, phrase);
+                }
+            }
+            None => self.index_text(phrase, phrase),
+        }
+    }
+
+    /// Indexes the text as the phrase, so that searches matching the text find the phrase
+    fn index_text(&mut self, value: &str, phrase: &str) {
         if value.is_empty() {
             return;
         }
 
-        let lowercased = value.to_ascii_lowercase();
+        for (key, skip) in &self.keys(value) {
+            self.root.insert(key, phrase, skip);
+        }
+
+        if let Some(ngrams) = &mut self.ngrams {
+            ngrams.learn(phrase, value);
+        }
+
+        if let Some(index) = &mut self.phonetic {
+            for code in phonetic_codes(value) {
+                index.entry(code).or_default().insert(phrase.to_string());
+            }
+        }
+    }
+
+    /// Removes the text indexed as the phrase, leaving the rest of the phrase book as it is
+    fn unindex_text(&mut self, value: &str, phrase: &str) {
+        for (key, skip) in &self.keys(value) {
+            self.root.remove(key, phrase, skip);
+        }
+
+        if let Some(index) = &mut self.phonetic {
+            for code in phonetic_codes(value) {
+                if let Some(phrases) = index.get_mut(&code) {
+                    phrases.remove(phrase);
+                    if phrases.is_empty() {
+                        index.remove(&code);
+                    }
+                }
+            }
+        }
+    }
+
+    /// The analyzed keys of the text, along with the characters no suffix starts at
+    fn keys(&self, value: &str) -> Vec<(String, Vec<bool>)> {
+        let stopped = self.stopped(value);
+        let analysis = self.analysis();
+        let mut keys: Vec<(String, Vec<bool>)> = analysis
+            .phrase(value, false)
+            .into_iter()
+            .chain(analysis.phrase(value, true))
+            .map(|text| {
+                let key = text.key();
+                let graphemes = grapheme_starts(&key);
+                let skip = text
+                    .origins()
+                    .iter()
+                    .enumerate()
+                    .map(|(i, &origin)| {
+                        let stopped = stopped.get(origin).copied().unwrap_or(false);
+                        !graphemes[i] || (i > 0 && stopped)
+                    })
+                    .collect();
+                (key, skip)
+            })
+            .collect();
+        keys.sort_unstable();
+        keys.dedup();
+        keys
+    }
+
+    /// Whether each character of the value is part of a stop word
+    fn stopped(&self, value: &str) -> Vec<bool> {
+        boundary::stopped(&self.stop_words, &value.chars().collect::<Vec<_>>())
+    }
+}
+
+/// The phonetic codes of every word of the text
+fn phonetic_codes(value: &str) -> Vec<String> {
+    let chars: Vec<char> = value.chars().collect();
+    phonetic::words(value)
+        .into_iter()
+        .flat_map(|word| phonetic::codes(&chars[word].iter().collect::<String>()))
+        .collect()
+}
+
+/// The byte offsets of the suffixes of the key which are not skipped, along with the empty one
+fn suffixes<'k>(key: &'k str, skip: &'k [bool]) -> impl Iterator<Item = usize> + 'k {
+    key.char_indices()
+        .enumerate()
+        .filter(|&(n, _)| !skip.get(n).copied().unwrap_or(false))
+        .map(|(_, (i, _))| i)
+        .chain(iter::once(key.len()))
+}
 
+impl Node {
+    /// Inserts every suffix of the key, except those starting at a skipped character, such as
+    /// one inside a grapheme cluster
+    fn insert(&mut self, key: &str, word: &str, skip: &[bool]) {
         // experiment with not doing this... its possible that the search algorithm could account for suffixes?
-        for i in 0..=value.len() {
-            self.m_insert(value[i..].chars(), value);
-            if lowercased != value {
-                self.m_insert(lowercased[i..].chars(), value)
+        for i in suffixes(key, skip) {
+            self.m_insert(key[i..].chars(), word);
+        }
+    }
+
+    /// Removes the word from the suffixes of the key inserted by [insert](Node::insert),
+    /// pruning the nodes left empty
+    fn remove(&mut self, key: &str, word: &str, skip: &[bool]) {
+        for i in suffixes(key, skip) {
+            self.m_remove(key[i..].chars(), word);
+        }
+    }
+
+    /// Removes the word at the end of the value, returning whether the node is left empty
+    fn m_remove(&mut self, mut value: Chars<'_>, word: &str) -> bool {
+        match value.next() {
+            Some(c) => {
+                if let Some(child) = self.children.get_mut(&c) {
+                    if child.m_remove(value, word) {
+                        self.children.remove(&c);
+                    }
+                }
+            }
+            None => {
+                self.word.remove(word);
             }
         }
+        self.children.is_empty() && self.word.is_empty()
     }
 
     fn m_insert(&mut self, mut value: Chars<'_>, word: &str) {
@@ -57,29 +508,38 @@ impl TrieNode {
         }
     }
 
-    /// Not documenting due to changes to the api
-    pub fn search(&self, value: &str) -> HashSet<&str> {
-        match value.chars().any(|c| c.is_uppercase()) {
-            false => self.search_case_insensitive(value.chars()),
-            _ => todo!(),
-        }
+    pub(crate) fn child(&self, c: char) -> Option<&Node> {
+        self.children.get(&c)
     }
 
-    fn search_case_insensitive(&self, mut value: Chars<'_>) -> HashSet<&str> {
+    fn search(
+        &self,
+        mut value: impl Iterator<Item = char>,
+        keep: &dyn Fn(&str) -> bool,
+        interrupt: &Interrupt<'_>,
+    ) -> HashSet<&str> {
         match value.next() {
-            Some(c) => match self.children.get(&c.to_ascii_lowercase()) {
-                Some(node) => node.search_case_insensitive(value).drain().collect(),
+            Some(c) => match self.children.get(&c) {
+                Some(node) => node.search(value, keep, interrupt),
                 None => HashSet::new(),
             },
-            None => HashSet::from_iter(self.collect()),
+            None => HashSet::from_iter(self.collect(keep, interrupt)),
         }
     }
 
-    fn collect(&self) -> Vec<&str> {
+    /// The phrases below the node, stopping at the nodes reached after an interrupt
+    pub(crate) fn collect(
+        &self,
+        keep: &dyn Fn(&str) -> bool,
+        interrupt: &Interrupt<'_>,
+    ) -> Vec<&str> {
+        if interrupt.check() {
+            return Vec::new();
+        }
         self.children
             .values()
-            .flat_map(|v| v.collect())
-            .chain(self.word.iter().map(|x| x.as_str()))
+            .flat_map(|v| v.collect(keep, interrupt))
+            .chain(self.word.iter().map(|x| x.as_str()).filter(|x| keep(x)))
             .collect_vec()
     }
 }
@@ -87,6 +547,7 @@ impl TrieNode {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::analyzer::AndExpansion;
     use itertools::assert_equal;
 
     #[test]
@@ -129,6 +590,98 @@ mod tests {
         assert_equal(expected, actual)
     }
 
+    #[test]
+    fn stop_words_are_not_suffix_starting_points() {
+        let mut trie = TrieNode::default().with_stop_words(["THE".to_string()].into());
+        trie.insert("The science test");
+        trie.insert("pass the test");
+        trie.insert("bathe");
+        trie.insert("TheEnd");
+
+        assert_equal(
+            trie.search("the").iter().sorted(),
+            vec![&"The science test", &"TheEnd", &"bathe"],
+        );
+        assert_equal(trie.search("he").iter().sorted(), vec![&"bathe"]);
+        assert_equal(trie.search("end"), vec!["TheEnd"]);
+        assert_equal(
+            trie.search("test").iter().sorted(),
+            vec![&"The science test", &"pass the test"],
+        );
+    }
+
+    #[test]
+    fn expanding_analyzers_index_past_the_original_phrase() {
+        let mut trie = TrieNode::default().with_analyzer(AndExpansion);
+        trie.insert("R&D team");
+        let mut trie = trie.with_stop_words(["and".to_string()].into());
+        trie.insert("Salt&Pepper");
+
+        assert!(trie.search("and D").contains("R&D team"));
+        assert!(trie.search("team").contains("R&D team"));
+        assert!(trie.search("Pepper").contains("Salt&Pepper"));
+    }
+
+    #[test]
+    fn predictions_do_not_depend_on_reindexing() {
+        let mut trie = TrieNode::default().with_next_word_prediction(2);
+        for phrase in ["a b", "a b", "a c"] {
+            trie.insert(phrase);
+        }
+        let scores = |trie: &TrieNode| -> Vec<(String, f64)> {
+            trie.predict("a ")
+                .into_iter()
+                .map(|p| (p.word.to_string(), p.score))
+                .collect()
+        };
+
+        let inserted = scores(&trie);
+        assert_eq!(inserted[..2], [("b".into(), 0.5), ("c".into(), 0.5)]);
+        assert_eq!(scores(&trie.with_phonetic_index(true)), inserted);
+    }
+
+    #[test]
+    fn replacing_a_record_removes_its_previous_fields() {
+        let mut trie = TrieNode::default()
+            .with_phonetic_index(true)
+            .with_next_word_prediction(2);
+        trie.insert_record("book", &[("title", "Hello World"), ("tags", "help")]);
+        trie.insert("help desk");
+        trie.insert_record("book", &[("title", "Goodbye Moon")]);
+
+        assert!(trie.search("world").is_empty());
+        assert_equal(trie.search("hel"), vec!["help desk"]);
+        assert_equal(trie.search("moon"), vec!["book"]);
+        assert!(trie.phonetic_search("wurld").is_empty());
+        assert_equal(trie.phonetic_search("mune"), vec!["book"]);
+        assert!(trie.predict("hello ").iter().all(|p| p.word != "world"));
+        assert_eq!(trie.predict("goodbye ")[0].word, "moon");
+        assert!(trie.root.child('w').is_none());
+    }
+
+    #[test]
+    fn a_record_replaces_a_phrase_indexed_by_itself() {
+        let mut trie = TrieNode::default().with_next_word_prediction(2);
+        trie.insert("rust handbook");
+        trie.insert_record("rust handbook", &[("title", "The Rust Book")]);
+
+        assert!(trie.search("hand").is_empty());
+        assert_equal(trie.search("the rust"), vec!["rust handbook"]);
+        assert!(trie.predict("rust ").iter().all(|p| p.word != "handbook"));
+    }
+
+    #[test]
+    fn filtered_search_skips_phrases_without_matching_metadata() {
+        let mut trie = TrieNode::default();
+        trie.insert_with_metadata("hello", [("lang", Value::from("en"))]);
+        trie.insert_with_metadata("hallo", [("lang", Value::from("de"))]);
+        trie.insert("help");
+
+        let filter = Filter::eq("lang", "en");
+        assert_equal(trie.filtered_search("h", Some(&filter)), vec!["hello"]);
+        assert_eq!(trie.filtered_search("h", None).len(), 3);
+    }
+
     // #[ignore = "WIP"]
     #[test]
     fn get_middle_completions() {
@@ -148,4 +701,71 @@ mod tests {
         dbg!(&actual);
         assert_equal(expected.iter().sorted(), actual.iter().sorted())
     }
+
+    #[test]
+    fn payloads_are_attached_to_phrases() {
+        let mut trie = TrieNode::new();
+        trie.insert_with_payload("hello", 1);
+        trie.insert_with_payload("Hello", 2);
+        trie.insert("help");
+
+        assert_eq!(trie.payload("hello"), Some(&1));
+        assert_eq!(trie.payload("Hello"), Some(&2));
+        assert_eq!(trie.payload("help"), None);
+        assert_equal(
+            trie.search("hel").iter().sorted(),
+            ["Hello", "hello", "help"].iter(),
+        );
+    }
+
+    #[test]
+    fn search_is_normalized_like_the_phrases() {
+        let mut trie = TrieNode::default();
+        for val in ["Café", "ÉCOLE", "Straße", "cafe"] {
+            trie.insert(val);
+        }
+
+        assert_equal(trie.search("café").iter().sorted(), ["Café"].iter());
+        assert_equal(trie.search("cafe\u{301}").iter().sorted(), ["Café"].iter());
+        assert_equal(trie.search("éco").iter().sorted(), ["ÉCOLE"].iter());
+        assert_equal(trie.search("ÉCO").iter().sorted(), ["ÉCOLE"].iter());
+        assert_equal(trie.search("strasse").iter().sorted(), ["Straße"].iter());
+    }
+
+    #[test]
+    fn accent_stripping_matches_unaccented_searches() {
+        let mut trie = TrieNode::default();
+        trie.insert("Café");
+        let mut trie = trie.with_analyzer(Normalizer::new().with_accent_stripping(true));
+        trie.insert("crème brûlée");
+
+        assert_equal(trie.search("cafe").iter().sorted(), ["Café"].iter());
+        assert_equal(
+            trie.search("creme").iter().sorted(),
+            ["crème brûlée"].iter(),
+        );
+        assert_equal(trie.search("Cafe").iter().sorted(), ["Café"].iter());
+    }
+
+    #[test]
+    fn insert_and_search_multibyte_phrases() {
+        let mut trie = TrieNode::default();
+        for phrase in [
+            "naïve",
+            "q\u{303}uick",
+            "👨\u{200d}👩\u{200d}👧 family",
+            "東京タワー",
+        ] {
+            trie.insert(phrase);
+        }
+
+        assert!(trie.search("ïve").contains("naïve"));
+        assert!(trie.search("\u{303}u").is_empty());
+        assert!(trie.search("👩").is_empty());
+        assert!(trie
+            .search("👨\u{200d}👩")
+            .contains("👨\u{200d}👩\u{200d}👧 family"));
+        assert!(trie.search("タワー").contains("東京タワー"));
+        assert_eq!(trie.search("").len(), 4);
+    }
 }